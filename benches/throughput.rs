@@ -0,0 +1,104 @@
+// Measures how quickly the render thread gets through events once it's
+// tracking a large number of tasks. Run with `cargo bench`.
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use cli_status_board::{Frame, Renderer, SBState, SBStateConfig, Status};
+
+const NUM_TASKS: usize = 100_000;
+const NUM_SUBTASKS: usize = 100_000;
+const BATCH_SIZE: usize = 100;
+
+// Lays out every frame like normal, but doesn't bother drawing it anywhere.
+// Only counts them, so that we know the render thread kept up.
+struct NullRenderer(Arc<AtomicUsize>);
+
+impl Renderer for NullRenderer {
+    fn size(&self) -> Option<(usize, usize)> {
+        Some((120, 40))
+    }
+
+    fn render(&mut self, _frame: &Frame) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+// Runs `send` against a fresh board, then waits until every event has been applied.
+// It gets how many frames have been drawn so far, and returns how many events it sent.
+fn measure<F: FnOnce(&SBState, &AtomicUsize) -> usize>(
+    description: &str,
+    config: SBStateConfig,
+    send: F,
+) {
+    let num_frames = Arc::new(AtomicUsize::new(0));
+    let state = SBState::with_renderer(config, NullRenderer(num_frames.clone()));
+
+    let start = Instant::now();
+    let num_events = send(&state, &num_frames);
+    state.shutdown();
+    let elapsed = start.elapsed();
+
+    println!(
+        "{}: {} events in {:.3}s ({:.0} events/s, {} frames)",
+        description,
+        num_events,
+        elapsed.as_secs_f64(),
+        num_events as f64 / elapsed.as_secs_f64(),
+        num_frames.load(Ordering::Relaxed)
+    );
+}
+
+fn main() {
+    measure(
+        &format!("{} tasks", NUM_TASKS),
+        SBStateConfig::default(),
+        |state, _| {
+            let task_ids = (0..NUM_TASKS)
+                .map(|index| state.add_task(format!("Task {index}"), Status::Queued))
+                .collect::<Vec<_>>();
+            for task_id in &task_ids {
+                state.update_task(task_id, Status::Started);
+                // The handle drops straight away, which finishes the subtask
+                state.add_subtask(task_id, Status::Started);
+            }
+            // Finishes every task as the handles drop
+            drop(task_ids);
+            NUM_TASKS * 5
+        },
+    );
+
+    // Every frame shows the progress of every subtask, so make sure plenty get built
+    // while the subtasks finish one by one, waiting for a new frame after every batch.
+    measure(
+        &format!("{} subtasks under one task", NUM_SUBTASKS),
+        SBStateConfig {
+            refresh_rate: Duration::ZERO,
+            ..Default::default()
+        },
+        |state, num_frames| {
+            let task_id = state.add_task("Task", Status::Started);
+            let subtask_ids = (0..NUM_SUBTASKS)
+                .map(|index| {
+                    state.add_named_subtask(&task_id, format!("Subtask {index}"), Status::Started)
+                })
+                .collect::<Vec<_>>();
+            for batch in subtask_ids.chunks(BATCH_SIZE) {
+                for subtask_id in batch {
+                    state.update_subtask(&task_id, subtask_id, Status::Finished);
+                }
+                let num_frames_before = num_frames.load(Ordering::Relaxed);
+                while num_frames.load(Ordering::Relaxed) == num_frames_before {
+                    std::thread::yield_now();
+                }
+            }
+            // Dropping the handles tries to finish everything once more
+            NUM_SUBTASKS * 3 + 2
+        },
+    );
+}
@@ -7,6 +7,7 @@ fn main() {
         silent: false,
         task_name_width: TaskNameWidth::ExactRatio(0.25),
         grow_if_no_progress: false,
+        max_subtasks_shown: 1,
         ..Default::default()
     });
 
@@ -23,7 +24,6 @@ fn main() {
     }
 
     let mut tasks = (0..10)
-        .into_iter()
         .map(|index| {
             let state = state.clone();
             std::thread::spawn(move || {
@@ -31,11 +31,17 @@ fn main() {
                 let task_id = state.add_task(format!("Task {index}"), Status::Started);
                 std::thread::sleep(Duration::from_secs(index.min(3)));
                 let sub_tasks = (0..10)
-                    .into_iter()
-                    .map(|_| state.add_subtask(&task_id, Status::Started))
+                    .map(|sub_index| {
+                        state.add_named_subtask(
+                            &task_id,
+                            format!("Subtask {sub_index}"),
+                            Status::Queued,
+                        )
+                    })
                     .collect::<Vec<_>>();
 
                 for sub_task_id in sub_tasks {
+                    state.update_subtask(&task_id, &sub_task_id, Status::Started);
                     std::thread::sleep(Duration::from_secs((index + 1).min(3)));
                     state.update_subtask(&task_id, &sub_task_id, Status::Finished);
                 }
@@ -49,7 +55,7 @@ fn main() {
         let state = state.clone();
         move || {
             let task_id = state.add_task(
-                format!("Task with looooooooooooooooooooooooooooooooooooooong message. I mean, it's soooooooooooooooooooooooooooooooo loooooooooooooooooooooooooooooooooooooooooonnnnnnnnnnnnnnnnnnngggggggggggggggggg"),
+                "Task with looooooooooooooooooooooooooooooooooooooong message. I mean, it's soooooooooooooooooooooooooooooooo loooooooooooooooooooooooooooooooooooooooooonnnnnnnnnnnnnnnnnnngggggggggggggggggg",
                 Status::Started,
             );
             std::thread::sleep(Duration::from_secs(10));
@@ -60,12 +66,26 @@ fn main() {
     tasks.push(std::thread::spawn({
         let state = state.clone();
         move || {
-            let task_id = state.add_task(format!("Task with no children"), Status::Started);
+            let task_id = state.add_task("Task with no children", Status::Started);
             std::thread::sleep(Duration::from_secs(10));
             state.update_task(&task_id, Status::Finished);
         }
     }));
 
+    tasks.push(std::thread::spawn({
+        let state = state.clone();
+        move || {
+            // Large units of work can report their progress directly, without subtasks
+            let task_id = state.add_task("Downloading something big", Status::Started);
+            let total_bytes = 10_000;
+            state.set_progress(&task_id, 0, total_bytes);
+            for _ in 0..100 {
+                std::thread::sleep(Duration::from_millis(100));
+                state.inc_progress(&task_id, total_bytes / 100);
+            }
+        }
+    }));
+
     tasks.push(std::thread::spawn(move || {
         std::thread::sleep(Duration::from_secs(4));
         state.info("Here's an informational message");
@@ -9,7 +9,7 @@ fn main() {
     });
 
     // When this handle drops the task completes
-    let _handle = state.add_task(format!("Some super basic task"), Status::Started);
+    let _handle = state.add_task("Some super basic task", Status::Started);
 
     std::thread::sleep(Duration::from_secs(10));
 }
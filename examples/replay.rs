@@ -0,0 +1,44 @@
+// Plays back an event log recorded with `SBStateConfig::event_log_path`.
+// cargo run --example replay --features serde -- <log.jsonl> [speed|instant]
+use std::{fs::File, io::BufReader};
+
+use cli_status_board::{ReplaySpeed, SBState, SBStateConfig};
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let Some(path) = args.next() else {
+        eprintln!("Usage: replay <log.jsonl> [speed|instant]");
+        std::process::exit(1);
+    };
+
+    let speed = match args.next().as_deref() {
+        None => ReplaySpeed::Original,
+        Some("instant") => ReplaySpeed::Instant,
+        Some(factor) => match factor.parse() {
+            Ok(factor) => ReplaySpeed::Scaled(factor),
+            Err(_) => {
+                eprintln!(
+                    "Expected a speed like 2.0, or \"instant\", but got {}",
+                    factor
+                );
+                std::process::exit(1);
+            }
+        },
+    };
+
+    let log = match File::open(&path) {
+        Ok(file) => BufReader::new(file),
+        Err(err) => {
+            eprintln!("Couldn't open {}: {}", path, err);
+            std::process::exit(1);
+        }
+    };
+
+    let state = SBState::new(SBStateConfig::default());
+    let result = state.replay(log, speed);
+    state.shutdown();
+    if let Err(err) = result {
+        eprintln!("Couldn't replay {}: {}", path, err);
+        std::process::exit(1);
+    }
+}
@@ -1,9 +1,14 @@
 use colored::ColoredString;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 #[derive(Clone, Debug)]
 pub struct Column {
     data: Vec<ColoredString>,
     original_data: Vec<ColoredString>,
+
+    // When wrapping, whatever didn't fit on each row's first line
+    overflow: Vec<Vec<String>>,
     config: ColumnConfig,
     pub longest: usize,
 }
@@ -14,6 +19,13 @@ pub struct ColumnConfig {
     pub fit: ColumnFit,
     pub left_padding: usize,
     pub right_padding: usize,
+
+    // Which part of anything too long to cut, and what to replace it with
+    pub truncate: ColumnTruncate,
+    pub ellipsis: String,
+
+    // Instead of cutting anything too long, carry the rest over onto extra lines
+    pub wrap: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -22,6 +34,21 @@ pub enum ColumnAlign {
     RIGHT,
 }
 
+#[derive(Clone, Copy, Debug)]
+pub enum ColumnTruncate {
+    // Keep the start, e.g. "crates/foo/s..."
+    END,
+
+    // Keep the end, e.g. "...deep/module.rs"
+    START,
+
+    // Keep both ends, e.g. "crates/...module.rs"
+    MIDDLE,
+
+    // Drop whole leading directories, e.g. ".../deep/module.rs"
+    PATH,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum ColumnFit {
     NORMAL,
@@ -36,61 +63,38 @@ impl Column {
             config,
             data: Vec::new(),
             original_data: Vec::new(),
+            overflow: Vec::new(),
             longest: 0,
         }
     }
 
     pub fn push(&mut self, mut value: ColoredString) {
+        let index = self.original_data.len();
         self.original_data.push(value.clone());
+        self.overflow.push(Vec::new());
 
         match self.config.fit {
             ColumnFit::EXACT(len) => {
-                let len = len - self.config.left_padding - self.config.right_padding;
-                if value.input.len() > len {
-                    value.input = value
-                        .input
-                        .chars()
-                        .take(len - 3)
-                        .chain("...".chars())
-                        .collect();
-                } else if value.input.len() < len {
-                    match &self.config.align {
-                        ColumnAlign::LEFT => {
-                            value.input = format!("{: <width$}", value.input, width = len);
-                        }
-                        ColumnAlign::RIGHT => {
-                            value.input = format!("{: >width$}", value.input, width = len);
-                        }
-                    }
-                }
+                let len = len
+                    .saturating_sub(self.config.left_padding)
+                    .saturating_sub(self.config.right_padding);
+                value.input = pad(&self.fit(index, &value.input, len), len, &self.config.align);
             }
             ColumnFit::MIN(min_len) => {
-                let min_len = min_len - self.config.left_padding - self.config.right_padding;
-                if value.input.len() < min_len {
-                    match &self.config.align {
-                        ColumnAlign::LEFT => {
-                            value.input = format!("{: <width$}", value.input, width = min_len);
-                        }
-                        ColumnAlign::RIGHT => {
-                            value.input = format!("{: >width$}", value.input, width = min_len);
-                        }
-                    }
-                }
+                let min_len = min_len
+                    .saturating_sub(self.config.left_padding)
+                    .saturating_sub(self.config.right_padding);
+                value.input = pad(&value.input, min_len, &self.config.align);
             }
             ColumnFit::MAX(max_len) => {
-                let max_len = max_len - self.config.left_padding - self.config.right_padding;
-                if value.input.len() > max_len {
-                    value.input = value
-                        .input
-                        .chars()
-                        .take(max_len - 3)
-                        .chain("...".chars())
-                        .collect();
-                }
+                let max_len = max_len
+                    .saturating_sub(self.config.left_padding)
+                    .saturating_sub(self.config.right_padding);
+                value.input = self.fit(index, &value.input, max_len);
             }
             ColumnFit::NORMAL => {}
         }
-        self.longest = self.longest.max(value.len());
+        self.longest = self.longest.max(display_width(&value.input));
         self.data.push(value);
     }
 
@@ -99,32 +103,20 @@ impl Column {
             return String::new();
         }
 
-        match self.config.align {
-            ColumnAlign::LEFT => format!(
-                "{:left_padding$}{: <width$}{:right_padding$}",
-                "",
-                self.data[index],
-                "",
-                left_padding = self.config.left_padding,
-                width = self.longest,
-                right_padding = self.config.right_padding
-            ),
-            ColumnAlign::RIGHT => format!(
-                "{:left_padding$}{: >width$}{:right_padding$}",
-                "",
-                self.data[index],
-                "",
-                left_padding = self.config.left_padding,
-                width = self.longest,
-                right_padding = self.config.right_padding
-            ),
-        }
+        let mut value = self.data[index].clone();
+        value.input = pad(&value.input, self.longest, &self.config.align);
+        format!(
+            "{:left_padding$}{}{:right_padding$}",
+            "",
+            value,
+            "",
+            left_padding = self.config.left_padding,
+            right_padding = self.config.right_padding
+        )
     }
 
     pub fn to_wide_string(&mut self, index: usize, available_width: usize) -> String {
-        if index >= self.original_data.len()
-            || available_width < (self.config.left_padding + self.config.right_padding).max(3)
-        {
+        if index >= self.original_data.len() || available_width < self.padding() {
             return String::new();
         }
 
@@ -132,45 +124,345 @@ impl Column {
             available_width - self.config.left_padding - self.config.right_padding;
 
         let mut value = self.original_data[index].clone();
-        if value.input.len() > available_width {
-            value.input = value
-                .input
-                .chars()
-                .take(available_width - 3)
-                .chain("...".chars())
-                .collect();
-        }
-
-        match self.config.align {
-            ColumnAlign::LEFT => format!(
-                "{:left_padding$}{: <width$}{:right_padding$}",
-                "",
-                value,
-                "",
-                left_padding = self.config.left_padding,
-                width = available_width,
-                right_padding = self.config.right_padding
-            ),
-            ColumnAlign::RIGHT => format!(
-                "{:left_padding$}{: >width$}{:right_padding$}",
-                "",
+        value.input = pad(
+            &self.fit(index, &value.input, available_width),
+            available_width,
+            &self.config.align,
+        );
+        format!(
+            "{:left_padding$}{}{:right_padding$}",
+            "",
+            value,
+            "",
+            left_padding = self.config.left_padding,
+            right_padding = self.config.right_padding
+        )
+    }
+
+    // Whatever of this value fits on the row's own line, either truncated or,
+    // when wrapping, with the rest saved for `continuation_lines`.
+    fn fit(&mut self, index: usize, value: &str, max_width: usize) -> String {
+        // Wrapping can't help when a single grapheme is too wide for a line of its own
+        // (e.g. wide characters in a really narrow column), so cut it short instead
+        let rest_width = max_width.saturating_sub(2);
+        let widest = segments(value)
+            .filter(|(_, is_escape)| !is_escape)
+            .map(|(grapheme, _)| grapheme.width())
+            .max()
+            .unwrap_or_default();
+        if !self.config.wrap || widest > rest_width {
+            return truncate(
                 value,
-                "",
-                left_padding = self.config.left_padding,
-                width = available_width,
-                right_padding = self.config.right_padding
-            ),
+                max_width,
+                self.config.truncate,
+                &self.config.ellipsis,
+            );
         }
+
+        let mut lines = wrap(value, max_width, rest_width).into_iter();
+        let first_line = lines.next().unwrap_or_default();
+        self.overflow[index] = lines.collect();
+        return first_line;
+    }
+
+    // The extra lines a wrapped row needs, indented a little further than the row itself.
+    pub(crate) fn continuation_lines(&self, index: usize) -> Vec<String> {
+        let Some(original) = self.original_data.get(index) else {
+            return Vec::new();
+        };
+
+        self.overflow[index]
+            .iter()
+            .map(|line| {
+                let mut value = original.clone();
+                value.input = line.clone();
+                format!(
+                    "{:indent$}{}",
+                    "",
+                    value,
+                    indent = self.config.left_padding + 2
+                )
+            })
+            .collect()
     }
 
     pub(crate) fn line_len(&self) -> usize {
-        self.longest + self.config.left_padding + self.config.right_padding
+        self.longest + self.padding()
+    }
+
+    pub(crate) fn padding(&self) -> usize {
+        self.config.left_padding + self.config.right_padding
+    }
+
+    // The least room (including padding) this needs to show at least one grapheme of
+    // every value along with the ellipsis, after `indent` cells of indentation.
+    // Anything that fits whole needs no more than that.
+    pub(crate) fn min_line_len(&self, indent: usize) -> usize {
+        let widest = self
+            .original_data
+            .iter()
+            .flat_map(|value| segments(&value.input))
+            .filter(|(_, is_escape)| !is_escape)
+            .map(|(grapheme, _)| grapheme.width())
+            .max()
+            .unwrap_or_default();
+        let useful = indent + display_width(&self.config.ellipsis) + widest;
+        self.natural_width().min(useful) + self.padding()
+    }
+
+    // How wide the widest value is as given, before fitting it to anything.
+    pub(crate) fn natural_width(&self) -> usize {
+        self.original_data
+            .iter()
+            .map(|value| display_width(&value.input))
+            .max()
+            .unwrap_or_default()
+    }
+
+    // Lays every value out again with a different fit, e.g. once it's known how much
+    // room is actually left for this column.
+    pub(crate) fn refit(&mut self, fit: ColumnFit) {
+        self.config.fit = fit;
+        let values = std::mem::take(&mut self.original_data);
+        self.data.clear();
+        self.overflow.clear();
+        self.longest = 0;
+        for value in values {
+            self.push(value);
+        }
+    }
+
+    // For when there's no room left for anything but the values themselves.
+    pub(crate) fn remove_padding(&mut self) {
+        self.config.left_padding = 0;
+        self.config.right_padding = 0;
     }
 
     pub(crate) fn is_empty(&self, row_index: usize) -> bool {
         match self.data.get(row_index) {
-            Some(data) => data.len() == 0,
+            Some(data) => data.input.is_empty(),
             None => true,
         }
     }
 }
+
+// Splits a string into ANSI escape sequences (which don't take up any space)
+// and grapheme clusters (which take up however many cells the terminal gives them).
+fn segments(value: &str) -> impl Iterator<Item = (&str, bool)> {
+    let mut rest = value;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+
+        let (segment, is_escape) = if rest.starts_with('\x1b') {
+            // Up to and including the final letter
+            let end = rest
+                .char_indices()
+                .skip(1)
+                .find(|(_, c)| c.is_ascii_alphabetic())
+                .map(|(index, c)| index + c.len_utf8())
+                .unwrap_or(rest.len());
+            (&rest[..end], true)
+        } else {
+            let end = rest.find('\x1b').unwrap_or(rest.len());
+            let grapheme = rest[..end].graphemes(true).next().unwrap_or(&rest[..end]);
+            (grapheme, false)
+        };
+        rest = &rest[segment.len()..];
+        Some((segment, is_escape))
+    })
+}
+
+// How many terminal cells this takes up when displayed.
+pub(crate) fn display_width(value: &str) -> usize {
+    segments(value)
+        .filter(|(_, is_escape)| !is_escape)
+        .map(|(grapheme, _)| grapheme.width())
+        .sum()
+}
+
+// Cuts this down to fit within `max_width` cells, replacing whatever had to go
+// with the ellipsis. Never splits a grapheme, and keeps any escape sequences
+// so that colors still get reset.
+fn truncate(value: &str, max_width: usize, mode: ColumnTruncate, ellipsis: &str) -> String {
+    if display_width(value) <= max_width {
+        return value.to_string();
+    }
+
+    // Not even the ellipsis fits, so show as much of it as we can
+    let ellipsis_width = display_width(ellipsis);
+    if ellipsis_width > max_width {
+        return truncate(ellipsis, max_width, ColumnTruncate::END, "");
+    }
+    let budget = max_width - ellipsis_width;
+
+    let graphemes = segments(value)
+        .filter(|(_, is_escape)| !is_escape)
+        .map(|(grapheme, _)| grapheme)
+        .collect::<Vec<_>>();
+    let widths = graphemes
+        .iter()
+        .map(|grapheme| grapheme.width())
+        .collect::<Vec<_>>();
+
+    // How many graphemes from the start/end fit within some width
+    let prefix_len = |width: usize| -> usize {
+        let mut total = 0;
+        widths
+            .iter()
+            .take_while(|w| {
+                total += **w;
+                total <= width
+            })
+            .count()
+    };
+    let suffix_len = |width: usize| -> usize {
+        let mut total = 0;
+        widths
+            .iter()
+            .rev()
+            .take_while(|w| {
+                total += **w;
+                total <= width
+            })
+            .count()
+    };
+
+    // The graphemes in `from..to` get replaced by the ellipsis
+    let len = graphemes.len();
+    let (from, to) = match mode {
+        ColumnTruncate::END => (prefix_len(budget), len),
+        ColumnTruncate::START => (0, len - suffix_len(budget)),
+        ColumnTruncate::MIDDLE => {
+            let from = prefix_len(budget.div_ceil(2));
+            let head_width = widths[..from].iter().sum::<usize>();
+            (from, len - suffix_len(budget - head_width).min(len - from))
+        }
+        ColumnTruncate::PATH => {
+            // Everything from the earliest directory separator that leaves enough room
+            let suffix_start = len - suffix_len(budget);
+            let separator = (suffix_start..len).find(|index| graphemes[*index] == "/");
+            match separator {
+                Some(separator) => (0, separator),
+                None if graphemes.contains(&"/") => (0, suffix_start),
+                None => (prefix_len(budget), len),
+            }
+        }
+    };
+
+    let mut result = String::with_capacity(value.len() + ellipsis.len());
+    let mut index = 0;
+    for (segment, is_escape) in segments(value) {
+        if is_escape {
+            result += segment;
+            continue;
+        }
+
+        if index == from {
+            result += ellipsis;
+        }
+        if index < from || index >= to {
+            result += segment;
+        }
+        index += 1;
+    }
+    result
+}
+
+// Splits this into lines that each fit within `first_width` (for the first line)
+// or `rest_width` cells, breaking between words where possible and otherwise
+// between graphemes. Escape sequences take up no space and stay wherever they were.
+// Every grapheme needs to fit within both widths, or its line ends up too wide.
+fn wrap(value: &str, first_width: usize, rest_width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut line_width = 0;
+
+    // Where in `line` the latest space is, if any
+    let mut last_space = None;
+
+    for (segment, is_escape) in segments(value) {
+        if is_escape {
+            line += segment;
+            continue;
+        }
+
+        let width = if lines.is_empty() {
+            first_width
+        } else {
+            rest_width
+        };
+        let segment_width = segment.width();
+        if line_width + segment_width > width && line_width > 0 {
+            let rest = match last_space.take() {
+                Some(position) => {
+                    let rest = line.split_off(position);
+                    rest.trim_start_matches(' ').to_string()
+                }
+                None => String::new(),
+            };
+            lines.push(line.trim_end_matches(' ').to_string());
+            line = rest;
+
+            // Whatever got carried over can still be too long for a line of its own,
+            // or not leave enough room for what comes next
+            while display_width(&line) > rest_width {
+                let (head, tail) = split_at_width(&line, rest_width);
+                lines.push(head);
+                line = tail;
+            }
+            if display_width(&line) + segment_width > rest_width {
+                lines.push(std::mem::take(&mut line));
+            }
+            line_width = display_width(&line);
+
+            // Nothing before the break is a space anymore
+            if segment == " " && line_width == 0 {
+                continue;
+            }
+        }
+
+        if segment == " " {
+            last_space = Some(line.len());
+        }
+        line += segment;
+        line_width += segment_width;
+    }
+
+    lines.push(line);
+    lines
+}
+
+// Splits off as much of the start of this as fits within `width` cells,
+// always taking at least one grapheme so that it gets somewhere.
+fn split_at_width(value: &str, width: usize) -> (String, String) {
+    let mut head_len = 0;
+    let mut head_width = 0;
+    for (segment, is_escape) in segments(value) {
+        if !is_escape {
+            let segment_width = segment.width();
+            if head_width + segment_width > width && head_width > 0 {
+                break;
+            }
+            head_width += segment_width;
+        }
+        head_len += segment.len();
+    }
+    (value[..head_len].to_string(), value[head_len..].to_string())
+}
+
+// Cuts off anything past `max_width` cells, e.g. so that a line never
+// spills past the edge of the terminal.
+pub(crate) fn clamp_width(value: &str, max_width: usize) -> String {
+    truncate(value, max_width, ColumnTruncate::END, "")
+}
+
+// Pads this out to at least `width` cells.
+fn pad(value: &str, width: usize, align: &ColumnAlign) -> String {
+    let padding = " ".repeat(width.saturating_sub(display_width(value)));
+    match align {
+        ColumnAlign::LEFT => format!("{}{}", value, padding),
+        ColumnAlign::RIGHT => format!("{}{}", padding, value),
+    }
+}
@@ -0,0 +1,38 @@
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    // The status board isn't running anymore (either it was shut down or
+    // its render thread crashed), so there's nobody to send updates to.
+    Disconnected,
+
+    // The render thread panicked at some point before shutting down.
+    RenderThreadPanicked,
+
+    // A file (e.g. a report or an event log) couldn't be read or written.
+    Io(std::io::ErrorKind),
+
+    // This line of an event log (counting from 1) isn't a recorded event.
+    InvalidEventLog(usize),
+
+    // Replays can only be sped up or slowed down by a positive factor.
+    InvalidReplaySpeed,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Disconnected => write!(f, "the status board is no longer running"),
+            Error::RenderThreadPanicked => write!(f, "the status board's render thread panicked"),
+            Error::Io(kind) => write!(f, "couldn't read or write a file: {}", kind),
+            Error::InvalidEventLog(line) => {
+                write!(f, "line {} of the event log isn't a recorded event", line)
+            }
+            Error::InvalidReplaySpeed => {
+                write!(f, "replays can only be scaled by a positive factor")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
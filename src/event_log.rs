@@ -0,0 +1,148 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    time::Instant,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::state::TaskEvent;
+
+// One line of the event log: an event, and how many seconds into the run it happened.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct LoggedEvent<E> {
+    pub time: f64,
+    pub event: E,
+}
+
+// Writes every event the render thread processes to a JSON Lines file.
+#[derive(Debug)]
+pub(crate) struct EventLog {
+    writer: BufWriter<File>,
+    start: Instant,
+}
+
+impl EventLog {
+    pub(crate) fn create(path: &Path) -> std::io::Result<Self> {
+        Ok(Self {
+            writer: BufWriter::new(File::create(path)?),
+            start: Instant::now(),
+        })
+    }
+
+    pub(crate) fn record(&mut self, event: &TaskEvent) -> std::io::Result<()> {
+        // Queries don't change anything
+        if matches!(event, TaskEvent::Summarize(_) | TaskEvent::ExportJUnit(_)) {
+            return Ok(());
+        }
+
+        let line = LoggedEvent {
+            time: self.start.elapsed().as_secs_f64(),
+            event,
+        };
+        serde_json::to_writer(&mut self.writer, &line)?;
+        writeln!(self.writer)
+    }
+
+    pub(crate) fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use std::io::BufRead;
+
+    use super::*;
+    use crate::{Status, TaskId};
+
+    // Fails to compile whenever an event is added, so that it gets covered below.
+    fn is_recorded(event: &TaskEvent) -> bool {
+        match event {
+            TaskEvent::AddTask(..)
+            | TaskEvent::SetTaskDisplayName(..)
+            | TaskEvent::UpdateTask(..)
+            | TaskEvent::DeleteTask(..)
+            | TaskEvent::AddSubTask(..)
+            | TaskEvent::UpdateSubTask(..)
+            | TaskEvent::SetProgress(..)
+            | TaskEvent::IncProgress(..)
+            | TaskEvent::FinishOnDrop(..)
+            | TaskEvent::Shutdown => true,
+            TaskEvent::Summarize(_) | TaskEvent::ExportJUnit(_) => false,
+        }
+    }
+
+    #[test]
+    fn reads_back_every_recorded_event() {
+        let task = TaskId::new();
+        let subtask = TaskId::new();
+        let events = vec![
+            TaskEvent::AddTask(task.clone(), Some("build".to_string()), Status::Queued),
+            TaskEvent::AddTask(TaskId::new(), None, Status::Info),
+            TaskEvent::SetTaskDisplayName(task.clone(), "build \"all\"\n".to_string()),
+            TaskEvent::UpdateTask(task.clone(), Status::Started),
+            TaskEvent::UpdateTask(task.clone(), Status::custom("Uploading")),
+            // Custom, but named like a built-in status
+            TaskEvent::UpdateTask(task.clone(), Status::custom("Queued")),
+            TaskEvent::AddSubTask(task.clone(), subtask.clone(), None, Status::Paused),
+            TaskEvent::AddSubTask(
+                task.clone(),
+                TaskId::new(),
+                Some("docs".to_string()),
+                Status::Blocked,
+            ),
+            TaskEvent::UpdateSubTask(task.clone(), subtask.clone(), Status::Error),
+            TaskEvent::SetProgress(task.clone(), 3, u64::MAX),
+            TaskEvent::IncProgress(task.clone(), 1),
+            TaskEvent::FinishOnDrop(subtask.clone(), Status::Finished),
+            TaskEvent::DeleteTask(task.clone()),
+            TaskEvent::Shutdown,
+            TaskEvent::Summarize(std::sync::mpsc::channel().0),
+            TaskEvent::ExportJUnit(std::sync::mpsc::channel().0),
+        ];
+
+        let path = std::env::temp_dir().join(format!("event-log-{}.jsonl", std::process::id()));
+        let mut log = EventLog::create(&path).unwrap();
+        for event in &events {
+            log.record(event).unwrap();
+        }
+        log.flush().unwrap();
+        drop(log);
+
+        let file = File::open(&path).unwrap();
+        let read_back = std::io::BufReader::new(file)
+            .lines()
+            .map(|line| {
+                let logged: LoggedEvent<TaskEvent> = serde_json::from_str(&line.unwrap()).unwrap();
+                format!("{:?}", logged.event)
+            })
+            .collect::<Vec<_>>();
+        std::fs::remove_file(&path).unwrap();
+
+        let expected = events
+            .iter()
+            .filter(|event| is_recorded(event))
+            .map(|event| format!("{:?}", event))
+            .collect::<Vec<_>>();
+        assert_eq!(read_back, expected);
+    }
+
+    #[test]
+    fn tags_custom_statuses() {
+        assert_eq!(
+            serde_json::to_string(&Status::Queued).unwrap(),
+            r#""Queued""#
+        );
+        assert_eq!(
+            serde_json::to_string(&Status::custom("Queued")).unwrap(),
+            r#"{"Custom":"Queued"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Status>(r#"{"Custom":"Queued"}"#).unwrap(),
+            Status::Custom("Queued")
+        );
+        assert!(serde_json::from_str::<Status>(r#""Uploading""#).is_err());
+    }
+}
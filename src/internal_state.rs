@@ -1,103 +1,191 @@
+use std::time::{Duration, Instant};
+
 use colored::{ColoredString, Colorize};
 use rustc_hash::FxHashMap;
 
 use crate::{
-    SBStateConfig, Status, TaskId,
-    column::{Column, ColumnAlign, ColumnConfig, ColumnFit},
-    task::Task,
+    Retention, SBStateConfig, SectionConfig, Status, StatusRegistry, TaskId, TaskNameTruncation,
+    column::{Column, ColumnAlign, ColumnConfig, ColumnFit, ColumnTruncate, display_width},
+    junit::{Outcome, TestCase, build_junit_report},
+    renderer::{FrameSection, Transition},
+    status_index::StatusIndices,
+    summary::{Summary, TaskSummary},
+    task::{LeafCounts, Task},
 };
 
-#[derive(Default, Debug, PartialEq, Eq, Clone)]
+// Every task, no matter how deeply nested, is stored by id so that applying
+// any event is constant time. Which tasks are where (and in what order)
+// is tracked separately, by status.
+#[derive(Debug)]
 pub struct InternalState {
-    pub task_map: FxHashMap<Status, Vec<Task>>,
+    tasks: FxHashMap<i32, Task>,
+
+    // Top-level tasks only. Subtasks are indexed by their parent.
+    roots: StatusIndices,
+
+    // How many top-level tasks were dropped per status, so that they still count.
+    num_evicted: FxHashMap<Status, usize>,
+
+    // Errors stop being shown after a while, but we keep track of them for the recap.
+    expired_errors: Vec<String>,
+
+    // What each status means, including any custom ones.
+    statuses: StatusRegistry,
+
+    start: Instant,
 }
 
 impl InternalState {
+    pub(crate) fn new(statuses: StatusRegistry) -> Self {
+        Self {
+            tasks: FxHashMap::default(),
+            roots: StatusIndices::default(),
+            num_evicted: FxHashMap::default(),
+            expired_errors: Vec::new(),
+            statuses,
+            start: Instant::now(),
+        }
+    }
+
+    pub(crate) fn statuses(&self) -> &StatusRegistry {
+        &self.statuses
+    }
+
+    // Anything that was never registered is treated as an unfinished task
+    // from then on, so that it's still counted and listed.
+    fn register_if_unknown(&mut self, status: Status) {
+        if self.statuses.get(status).is_none() {
+            self.statuses.register(status, false);
+        }
+    }
+
     pub(crate) fn get_total(&self) -> usize {
-        let num_started = match self.task_map.get(&Status::Started) {
-            Some(v) => v.len(),
-            None => 0,
-        };
-        let num_queued = match self.task_map.get(&Status::Queued) {
-            Some(v) => v.len(),
-            None => 0,
-        };
-        let num_finished = match self.task_map.get(&Status::Finished) {
-            Some(v) => v.len(),
-            None => 0,
-        };
-        return num_finished + num_queued + num_started;
+        self.statuses
+            .iter()
+            .filter(|definition| definition.counts_towards_total())
+            .map(|definition| self.count(definition.status))
+            .sum()
     }
 
-    // pub(crate) fn event(&mut self, key: TaskId) {
-    //     self.add_task(key, None, Status::Info);
-    // }
+    // Everything that's done, whether it actually finished, was cancelled or was skipped.
+    pub(crate) fn get_num_finished(&self) -> usize {
+        self.statuses
+            .iter()
+            .filter(|definition| definition.counts_as_finished())
+            .map(|definition| self.count(definition.status))
+            .sum()
+    }
 
-    // pub(crate) fn error(&mut self, key: TaskId) {
-    //     self.add_task(key, None, Status::Error);
-    // }
+    // How many top-level tasks have ended up with this status,
+    // including any that have since been evicted.
+    pub(crate) fn count(&self, status: Status) -> usize {
+        self.roots.len(status) + self.num_evicted.get(&status).copied().unwrap_or_default()
+    }
 
-    pub(crate) fn add_task(&mut self, key: TaskId, display_name: Option<String>, status: Status) {
-        self.task_map.entry(status).or_default().push(Task {
-            key,
-            display_name,
-            time: std::time::Instant::now(),
-            substate: InternalState::default(),
-        });
+    pub(crate) fn add_task(
+        &mut self,
+        key: TaskId,
+        display_name: Option<String>,
+        status: Status,
+    ) -> Transition {
+        self.register_if_unknown(status);
+        let id = key.id;
+        let task = Task::new(key, display_name, status, None);
+        let transition = Transition {
+            name: task.name(),
+            previous_status: None,
+            status,
+        };
+        self.remove_tree(id);
+        self.tasks.insert(id, task);
+        self.roots.insert(status, id);
+        return transition;
     }
 
     pub(crate) fn delete_task(&mut self, key: TaskId) {
-        for (status, tasks) in self.task_map.iter_mut() {
-            // Only delete tasks that aren't finished yet
-            if !status.is_finished() {
-                tasks.retain(|task| if task.key == key { false } else { true });
-            }
+        // Only delete tasks that aren't finished yet
+        match self.tasks.get(&key.id) {
+            Some(task) if !self.statuses.is_finished(task.status) => self.remove_tree(key.id),
+            _ => {}
         }
     }
 
     pub(crate) fn set_display_name(&mut self, key: TaskId, display_name: String) {
-        for (_, tasks) in self.task_map.iter_mut() {
-            for task in tasks {
-                if task.key == key {
-                    task.display_name = Some(display_name.to_string());
-                }
-            }
+        let Some(task) = self.tasks.get_mut(&key.id) else {
+            return;
+        };
+        let was_named = task.display_name.replace(display_name).is_some();
+
+        // Subtasks that only just got a name get listed from now on
+        let status = task.status;
+        if !was_named && let Some(parent) = task.parent.and_then(|id| self.tasks.get_mut(&id)) {
+            parent.named_children.insert(status, key.id);
         }
     }
 
-    // pub fn clear_display_name(&mut self, key: &str) {
-    //     for (_, tasks) in self.task_map.iter_mut() {
-    //         for task in tasks {
-    //             if task.key == key {
-    //                 task.display_name = None;
-    //             }
-    //         }
-    //     }
-    // }
+    pub(crate) fn update_task(&mut self, key: TaskId, new_status: Status) -> Option<Transition> {
+        self.register_if_unknown(new_status);
+        let task = self.tasks.get(&key.id)?;
+        let previous_status = task.status;
+        if previous_status == new_status {
+            return None;
+        }
+        let previous_counts = self.counts_for_parent(task);
 
-    pub(crate) fn update_task(&mut self, key: TaskId, new_status: Status) {
-        let mut overall_to_move = vec![];
-        for (status, tasks) in self.task_map.iter_mut() {
-            if *status == new_status {
-                continue;
+        let task = self.tasks.get_mut(&key.id)?;
+        task.set_status(new_status);
+        let transition = Transition {
+            name: task.name(),
+            previous_status: Some(previous_status),
+            status: new_status,
+        };
+        let (parent, is_named) = (task.parent, task.display_name.is_some());
+
+        let Some(parent) = parent else {
+            self.roots.remove(previous_status, key.id);
+            self.roots.insert(new_status, key.id);
+            return Some(transition);
+        };
+        let counts = self.counts_for_parent(&self.tasks[&key.id]);
+        if let Some(parent) = self.tasks.get_mut(&parent) {
+            parent.children.remove(previous_status, key.id);
+            parent.children.insert(new_status, key.id);
+            if is_named {
+                parent.named_children.remove(previous_status, key.id);
+                parent.named_children.insert(new_status, key.id);
             }
+        }
+        self.update_ancestors(Some(parent), previous_counts, counts);
+        return Some(transition);
+    }
 
-            tasks.retain_mut(|task| -> bool {
-                if task.key == key {
-                    task.time = std::time::Instant::now();
-                    overall_to_move.push(task.clone());
-                    return false;
-                }
-                return true;
-            });
+    // The last handle to a task dropped. Anything that already finished some other way
+    // (e.g. it was cancelled or errored) stays that way. Only top-level tasks transition.
+    pub(crate) fn finish_on_drop(&mut self, key: TaskId, status: Status) -> Option<Transition> {
+        let task = self.tasks.get(&key.id)?;
+        if self.statuses.is_finished(task.status) {
+            return None;
+        }
+
+        let is_top_level = task.parent.is_none();
+        let transition = self.update_task(key, status)?;
+        return is_top_level.then_some(transition);
+    }
+
+    pub(crate) fn set_progress(&mut self, key: TaskId, current: u64, total: u64) {
+        if let Some(task) = self.tasks.get_mut(&key.id) {
+            task.progress = Some((current, total));
         }
+    }
 
-        self.task_map
-            .entry(new_status)
-            .or_default()
-            .extend(overall_to_move);
+    pub(crate) fn inc_progress(&mut self, key: TaskId, amount: u64) {
+        if let Some(task) = self.tasks.get_mut(&key.id) {
+            let (current, total) = task.progress.unwrap_or_default();
+            task.progress = Some((current.saturating_add(amount), total));
+        }
     }
 
+    // The parent can be any task, no matter how deeply nested.
     pub(crate) fn add_subtask(
         &mut self,
         key: TaskId,
@@ -105,170 +193,744 @@ impl InternalState {
         display_name: Option<String>,
         status: Status,
     ) {
-        for (_, tasks) in self.task_map.iter_mut() {
-            for task in tasks {
-                if task.key == key {
-                    task.substate.add_task(subkey, display_name, status);
-                    return;
-                }
+        if !self.tasks.contains_key(&key.id) {
+            return;
+        }
+
+        self.register_if_unknown(status);
+        let id = subkey.id;
+        self.remove_tree(id);
+        let task = Task::new(subkey, display_name, status, Some(key.id));
+        let (counts, is_named) = (self.counts_for_parent(&task), task.display_name.is_some());
+        self.tasks.insert(id, task);
+        if let Some(parent) = self.tasks.get_mut(&key.id) {
+            parent.children.insert(status, id);
+            if is_named {
+                parent.named_children.insert(status, id);
             }
         }
+        self.update_ancestors(Some(key.id), LeafCounts::default(), counts);
     }
 
     pub(crate) fn update_subtask(&mut self, key: TaskId, subkey: TaskId, new_status: Status) {
-        for (_, tasks) in self.task_map.iter_mut() {
-            for task in tasks {
-                if task.key == key {
-                    task.substate.update_task(subkey.clone(), new_status);
-                }
+        match self.tasks.get(&subkey.id) {
+            Some(task) if task.parent == Some(key.id) => {
+                self.update_task(subkey, new_status);
             }
+            _ => {}
         }
     }
 
-    pub(crate) fn clear_old_entries(
+    // Removes a task and all of its descendants, wherever it is.
+    fn remove_tree(&mut self, id: i32) {
+        let Some(task) = self.tasks.remove(&id) else {
+            return;
+        };
+
+        match task.parent.and_then(|parent| self.tasks.get_mut(&parent)) {
+            Some(parent) => {
+                parent.children.remove(task.status, id);
+                parent.named_children.remove(task.status, id);
+            }
+            None => {
+                self.roots.remove(task.status, id);
+            }
+        };
+        self.update_ancestors(
+            task.parent,
+            self.counts_for_parent(&task),
+            LeafCounts::default(),
+        );
+
+        let mut to_remove = task.children.all().collect::<Vec<_>>();
+        while let Some(id) = to_remove.pop() {
+            if let Some(task) = self.tasks.remove(&id) {
+                to_remove.extend(task.children.all());
+            }
+        }
+    }
+
+    // How far along a task is, as (current, total).
+    // Explicitly reported progress takes priority over subtasks, which
+    // are counted by their leaves so that progress rolls up from any depth.
+    pub(crate) fn progress(&self, task: &Task) -> Option<(u64, u64)> {
+        if let Some(progress) = task.progress {
+            return Some(progress);
+        }
+
+        match task.leaf_counts {
+            LeafCounts { total: 0, .. } => None,
+            counts => Some((counts.finished as u64, counts.total as u64)),
+        }
+    }
+
+    // How many of the subtasks counted by `progress` failed, if that's where its progress
+    // comes from. Failures count towards the total but not as finished, so they show up.
+    pub(crate) fn num_failed_subtasks(&self, task: &Task) -> u64 {
+        if task.progress.is_some() {
+            return 0;
+        }
+
+        task.leaf_counts.failed as u64
+    }
+
+    // What a task adds to its parent's leaf counts. Without any subtasks of its own it's
+    // a leaf, and anything that's finished (or failed) counts all of its leaves as such, too.
+    // Messages aren't tasks, so they don't count at all unless they're failures.
+    fn counts_for_parent(&self, task: &Task) -> LeafCounts {
+        let Some(definition) = self.statuses.get(task.status) else {
+            return LeafCounts::default();
+        };
+        let total = task.leaf_counts.total.max(1);
+
+        if definition.is_failure {
+            LeafCounts {
+                finished: 0,
+                failed: total,
+                total,
+            }
+        } else if !definition.counts_towards_total() {
+            LeafCounts::default()
+        } else if definition.counts_as_finished() {
+            LeafCounts {
+                finished: total,
+                failed: 0,
+                total,
+            }
+        } else {
+            LeafCounts {
+                total,
+                ..task.leaf_counts
+            }
+        }
+    }
+
+    // Passes a change in what some task counts for on to its parent, then on up through
+    // every ancestor whose own counts for its parent change as a result.
+    fn update_ancestors(
         &mut self,
-        max_duration: std::time::Duration,
-        statuses: &[Status],
+        mut parent: Option<i32>,
+        mut old: LeafCounts,
+        mut new: LeafCounts,
     ) {
-        let now = std::time::Instant::now();
-        for (_, rows) in self
-            .task_map
-            .iter_mut()
-            .filter(|(status, _)| statuses.contains(status))
+        while let Some(id) = parent
+            && old != new
         {
-            rows.retain_mut(|r| now.duration_since(r.time) < max_duration);
+            let Some(task) = self.tasks.get(&id) else {
+                return;
+            };
+            let previous_counts = self.counts_for_parent(task);
+
+            let Some(task) = self.tasks.get_mut(&id) else {
+                return;
+            };
+            task.leaf_counts.replace(old, new);
+            parent = task.parent;
+
+            old = previous_counts;
+            new = self.counts_for_parent(&self.tasks[&id]);
         }
     }
 
-    pub(crate) fn print_list<F>(
+    // Every unfinished task in this tree (most active first), each followed by its own
+    // unfinished descendants, down to `subtask_depth`. Without a depth, only the named
+    // subtasks just below the top are included. Queued subtasks are left out unless
+    // `show_queued_subtasks` is set.
+    // Only the first `max_subtasks_shown` of each task are included, with `None`
+    // standing in for the rest.
+    fn collect_active_tree<'a>(
+        &'a self,
+        parent: &'a Task,
+        depth: usize,
+        config: &SBStateConfig,
+        rows: &mut Vec<(Option<&'a Task>, usize)>,
+    ) {
+        let (max_depth, named_only) = match config.subtask_depth {
+            0 => (1, true),
+            max_depth => (max_depth, false),
+        };
+        if depth > max_depth {
+            return;
+        }
+
+        let children = match named_only {
+            true => &parent.named_children,
+            false => &parent.children,
+        };
+        let mut children = self
+            .statuses
+            .active()
+            .filter(|status| config.show_queued_subtasks || self.statuses.is_running(*status))
+            .flat_map(|status| children.iter(status))
+            .filter_map(|id| self.tasks.get(&id));
+
+        for task in children.by_ref().take(config.max_subtasks_shown) {
+            rows.push((Some(task), depth));
+            self.collect_active_tree(task, depth + 1, config, rows);
+        }
+
+        if children.next().is_some() {
+            rows.push((None, depth));
+        }
+    }
+
+    // Forgets about the oldest top-level tasks with this status until
+    // what's left fits within the retention policy. They still count towards the totals.
+    // Returns whether anything was actually dropped.
+    pub(crate) fn apply_retention(&mut self, status: Status, retention: &Retention) -> bool {
+        let mut did_evict = false;
+
+        // Tasks are indexed in the order they got their status, so the oldest come first
+        if let Some(max_age) = retention.max_age {
+            while let Some(id) = self.roots.first(status) {
+                match self.tasks.get(&id) {
+                    Some(task) if task.status_time().elapsed() < max_age => break,
+                    _ => self.evict(status, id),
+                }
+                did_evict = true;
+            }
+        }
+
+        if let Some(max_count) = retention.max_count {
+            while self.roots.len(status) > max_count {
+                match self.roots.first(status) {
+                    Some(id) => self.evict(status, id),
+                    None => break,
+                }
+                did_evict = true;
+            }
+        }
+
+        return did_evict;
+    }
+
+    fn evict(&mut self, status: Status, id: i32) {
+        if self.is_failure(status)
+            && let Some(task) = self.tasks.get(&id)
+        {
+            self.expired_errors.push(task.name());
+        }
+
+        self.roots.remove(status, id);
+        self.remove_tree(id);
+        *self.num_evicted.entry(status).or_default() += 1;
+    }
+
+    fn is_failure(&self, status: Status) -> bool {
+        self.statuses
+            .get(status)
+            .is_some_and(|definition| definition.is_failure)
+    }
+
+    // The name of every top-level task that ever errored, oldest first.
+    pub(crate) fn all_errors(&self) -> Vec<String> {
+        let current_errors = self
+            .statuses
+            .iter()
+            .filter(|definition| definition.is_failure)
+            .flat_map(|definition| self.roots.iter(definition.status))
+            .filter_map(|id| self.tasks.get(&id))
+            .map(Task::name);
+        self.expired_errors
+            .iter()
+            .cloned()
+            .chain(current_errors)
+            .collect()
+    }
+
+    // Everything that's happened so far, including up to `max_slowest` of the slowest tasks.
+    pub(crate) fn summary(&self, max_slowest: usize) -> Summary {
+        let status_counts = self
+            .statuses
+            .iter()
+            .map(|definition| (definition.status, self.count(definition.status)))
+            .filter(|(_, count)| *count > 0)
+            .collect();
+
+        // Messages aren't actually tasks
+        let tasks = self
+            .statuses
+            .iter()
+            .filter(|definition| definition.counts_towards_total())
+            .flat_map(|definition| self.roots.iter(definition.status))
+            .filter_map(|id| self.tasks.get(&id))
+            .collect::<Vec<_>>();
+
+        let summarize = |task: &Task, run_time: Duration| TaskSummary {
+            name: task.name(),
+            status: task.status,
+            queue_wait: task.queue_wait(),
+            run_time,
+            history: task
+                .history
+                .iter()
+                .map(|(time, status)| (time.saturating_duration_since(self.start), *status))
+                .collect(),
+        };
+
+        let mut task_summaries = tasks
+            .iter()
+            .map(|task| summarize(task, task.run_time(&self.statuses).unwrap_or_default()))
+            .collect::<Vec<_>>();
+        task_summaries.sort_by_key(|task| task.history[0].0);
+
+        let mut slowest_tasks = tasks
+            .iter()
+            .filter_map(|task| Some(summarize(task, task.run_time(&self.statuses)?)))
+            .collect::<Vec<_>>();
+        slowest_tasks.sort_by_key(|task| std::cmp::Reverse(task.run_time));
+        slowest_tasks.truncate(max_slowest);
+
+        let subtask_completion = tasks
+            .iter()
+            .filter(|task| task.leaf_counts.total > 0)
+            .map(|task| {
+                (
+                    task.name(),
+                    task.leaf_counts.finished,
+                    task.leaf_counts.total,
+                )
+            })
+            .collect();
+
+        Summary {
+            wall_time: self.start.elapsed(),
+            status_counts,
+            slowest_tasks,
+            tasks: task_summaries,
+            errors: self.all_errors(),
+            subtask_completion,
+        }
+    }
+
+    // Every task as a JUnit XML report. Errors are failures, and anything
+    // that didn't get to finish is skipped. Info and warning messages are left out.
+    pub(crate) fn junit_report(&self) -> String {
+        let mut cases = Vec::new();
+
+        // Errors don't carry any text apart from their name. For messages added with
+        // `SBState::error` that's the error itself, so it doubles as the failure message.
+
+        // Only the names of errors that have since been evicted are left
+        for error in &self.expired_errors {
+            cases.push(TestCase {
+                name: error.clone(),
+                classname: error.clone(),
+                time: Duration::ZERO,
+                outcome: Outcome::Failed(error.clone()),
+            });
+        }
+
+        let mut to_visit: Vec<(i32, Option<String>)> = Vec::new();
+        for definition in self.statuses.iter().rev() {
+            if definition.counts_towards_total() || definition.is_failure {
+                to_visit.extend(
+                    self.roots
+                        .iter(definition.status)
+                        .rev()
+                        .map(|id| (id, None)),
+                );
+            }
+        }
+
+        // Depth first, so that each task is followed by its subtasks
+        while let Some((id, parent_path)) = to_visit.pop() {
+            let Some(task) = self.tasks.get(&id) else {
+                continue;
+            };
+
+            let name = task.name();
+            let (classname, path) = match parent_path {
+                Some(parent_path) => (parent_path.clone(), format!("{}/{}", parent_path, name)),
+                None => (name.clone(), name.clone()),
+            };
+            let outcome = match self.statuses.get(task.status) {
+                Some(definition) if definition.is_failure => Outcome::Failed(name.clone()),
+                Some(definition) if definition.is_abandoned => {
+                    Outcome::Skipped(task.status.name().to_string())
+                }
+                Some(definition) if definition.is_finished => Outcome::Passed,
+                _ => Outcome::Skipped(format!("Unfinished ({})", task.status.name())),
+            };
+
+            for definition in self.statuses.iter().rev() {
+                to_visit.extend(
+                    task.children
+                        .iter(definition.status)
+                        .rev()
+                        .map(|id| (id, Some(path.clone()))),
+                );
+            }
+
+            cases.push(TestCase {
+                name,
+                classname,
+                time: task.run_time(&self.statuses).unwrap_or_default(),
+                outcome,
+            });
+        }
+
+        build_junit_report(&cases, self.start.elapsed())
+    }
+
+    pub(crate) fn build_section(
         &self,
-        status: Status,
-        max: usize,
-        color_func: F,
+        section: &SectionConfig,
         terminal_width: usize,
         task_name_fit: ColumnFit,
         config: &SBStateConfig,
-    ) where
-        F: Fn(&str) -> ColoredString,
-    {
-        if let Some(jobs) = self.task_map.get(&status) {
-            if jobs.len() > 0 {
-                println!("\n{:?} ({}):", status, jobs.len());
-
-                let mut columns = vec![
-                    // name
-                    Column::new(ColumnConfig {
-                        align: ColumnAlign::LEFT,
-                        fit: task_name_fit,
-                        left_padding: 4,
-                        right_padding: 1,
-                    }),
-                    // # subjob finished
-                    Column::new(ColumnConfig {
-                        align: ColumnAlign::RIGHT,
-                        fit: ColumnFit::NORMAL,
-                        left_padding: 3,
-                        right_padding: 1,
-                    }),
-                    // # subjob total
-                    Column::new(ColumnConfig {
-                        align: ColumnAlign::RIGHT,
-                        fit: ColumnFit::NORMAL,
-                        left_padding: 0,
-                        right_padding: 1,
-                    }),
-                ];
-                let mut progresses = Vec::new();
-
-                let mut num_rows = 0;
-                for job in jobs.iter().take(max) {
-                    let name = job
-                        .display_name
-                        .clone()
-                        .unwrap_or_else(|| job.key.to_string());
-
-                    columns[0].push(color_func(&name));
-
-                    if job.num_substate_total() == 0 {
-                        columns[1].push("".into());
-                        columns[2].push("".into());
-                        progresses.push(None);
-                    } else {
-                        let total = job.num_substate_total();
-                        let finished = job.num_substate_finished();
-                        columns[1].push(format!("{} /", finished).into());
-                        columns[2].push(total.to_string().into());
-                        progresses.push(Some(finished as f32 / total as f32));
-                    }
-                    num_rows += 1;
+    ) -> Option<FrameSection> {
+        let status = section.status;
+        let num_jobs = self.roots.len(status);
+        if !section.visible || num_jobs == 0 {
+            return None;
+        }
+
+        let max = section.max_rows;
+        let color_func = |f: &str| -> ColoredString {
+            match section.color {
+                Some(color) => f.color(color),
+                None => f.into(),
+            }
+        };
+        let task_name_fit = if section.full_width {
+            ColumnFit::EXACT(terminal_width)
+        } else {
+            task_name_fit
+        };
+        let mut rows = Vec::new();
+
+        let mut columns = vec![
+            // name
+            Column::new(ColumnConfig {
+                align: ColumnAlign::LEFT,
+                fit: task_name_fit,
+                left_padding: 4,
+                right_padding: 1,
+                truncate: match config.task_name_truncation {
+                    TaskNameTruncation::End => ColumnTruncate::END,
+                    TaskNameTruncation::Start => ColumnTruncate::START,
+                    TaskNameTruncation::Middle => ColumnTruncate::MIDDLE,
+                    TaskNameTruncation::Path => ColumnTruncate::PATH,
+                },
+                ellipsis: config.ellipsis.clone(),
+                wrap: section.wrap,
+            }),
+            // # subjob finished
+            Column::new(ColumnConfig {
+                align: ColumnAlign::RIGHT,
+                fit: ColumnFit::NORMAL,
+                left_padding: 3,
+                right_padding: 1,
+                truncate: ColumnTruncate::END,
+                ellipsis: config.ellipsis.clone(),
+                wrap: false,
+            }),
+            // # subjob total
+            Column::new(ColumnConfig {
+                align: ColumnAlign::RIGHT,
+                fit: ColumnFit::NORMAL,
+                left_padding: 0,
+                right_padding: 1,
+                truncate: ColumnTruncate::END,
+                ellipsis: config.ellipsis.clone(),
+                wrap: false,
+            }),
+        ];
+
+        let mut add_timing_column = |enabled: bool| -> Option<usize> {
+            if !enabled {
+                return None;
+            }
+            columns.push(Column::new(ColumnConfig {
+                align: ColumnAlign::RIGHT,
+                fit: ColumnFit::NORMAL,
+                left_padding: 1,
+                right_padding: 1,
+                truncate: ColumnTruncate::END,
+                ellipsis: config.ellipsis.clone(),
+                wrap: false,
+            }));
+            Some(columns.len() - 1)
+        };
+        let queue_wait_column = add_timing_column(config.show_queue_wait);
+        let elapsed_column = add_timing_column(config.show_elapsed);
+        let eta_column = add_timing_column(config.show_eta);
+        let rate_column = add_timing_column(config.show_rate);
+
+        let mut progresses = Vec::new();
+
+        // Each task is followed by its descendants, indented to show the tree
+        let ids: Box<dyn Iterator<Item = i32>> = if section.newest_first {
+            Box::new(self.roots.iter(status).rev())
+        } else {
+            Box::new(self.roots.iter(status))
+        };
+
+        let mut tree = Vec::new();
+        for job in ids.filter_map(|id| self.tasks.get(&id)).take(max) {
+            tree.push((Some(job), 0));
+            self.collect_active_tree(job, 1, config, &mut tree);
+        }
+        let max_depth = tree
+            .iter()
+            .map(|(_, depth)| *depth)
+            .max()
+            .unwrap_or_default();
+
+        let mut num_rows = 0;
+        for (maybe_job, depth) in tree {
+            num_rows += 1;
+            let Some(job) = maybe_job else {
+                // There are too many subtasks to show them all
+                columns[0].push(format!("{:indent$}...", "", indent = depth * 2).into());
+                for column in columns.iter_mut().skip(1) {
+                    column.push("".into());
                 }
+                progresses.push(None);
+                continue;
+            };
+
+            let name = format!("{:indent$}{}", "", job.name(), indent = depth * 2);
+
+            columns[0].push(color_func(&name));
 
-                if jobs.len() > max {
-                    num_rows += 1;
-                    columns[0].push("...".into());
+            // Only running tasks are actually doing anything worth timing
+            let elapsed = match self.statuses.is_running(job.status) {
+                true => job.run_time(&self.statuses),
+                false => None,
+            };
+            let elapsed_progress = match (elapsed, self.progress(job)) {
+                (Some(elapsed), Some((current, total))) if current > 0 => {
+                    Some((elapsed, current, total))
                 }
+                _ => None,
+            };
+
+            if let Some(index) = queue_wait_column {
+                let queue_wait = if job.was_queued() {
+                    format!("queued {}", format_duration(job.queue_wait()))
+                } else {
+                    String::new()
+                };
+                columns[index].push(queue_wait.into());
+            }
+            if let Some(index) = elapsed_column {
+                columns[index].push(elapsed.map(format_duration).unwrap_or_default().into());
+            }
+            if let Some(index) = eta_column {
+                let eta = match elapsed_progress {
+                    Some((elapsed, current, total)) if total >= current => {
+                        // Anything too far off to even fit in a `Duration` isn't worth showing
+                        let remaining = (total - current) as f64 / current as f64;
+                        match Duration::try_from_secs_f64(elapsed.as_secs_f64() * remaining) {
+                            Ok(eta) => format!("ETA {}", format_duration(eta)),
+                            Err(_) => String::new(),
+                        }
+                    }
+                    _ => String::new(),
+                };
+                columns[index].push(eta.into());
+            }
+            if let Some(index) = rate_column {
+                let rate = match elapsed_progress {
+                    Some((elapsed, current, _)) if !elapsed.is_zero() => {
+                        format!("{:.1}/s", current as f64 / elapsed.as_secs_f64())
+                    }
+                    _ => String::new(),
+                };
+                columns[index].push(rate.into());
+            }
 
-                for row_index in 0..num_rows {
-                    println!(
-                        "{}",
-                        draw_line(
-                            terminal_width,
-                            &mut columns,
-                            row_index,
-                            progresses
-                                .get(row_index)
-                                .map(|e| e.to_owned())
-                                .unwrap_or_default(),
-                            config
-                        )
-                    );
+            match self.progress(job) {
+                None => {
+                    columns[1].push("".into());
+                    columns[2].push("".into());
+                    progresses.push(None);
+                }
+                Some((current, 0)) => {
+                    // Without a total there's no way to draw a meaningful bar
+                    columns[1].push(format!("{} /", current).into());
+                    columns[2].push("?".into());
+                    progresses.push(None);
+                }
+                Some((current, total)) => {
+                    columns[1].push(format!("{} /", current).into());
+                    let total_text = match self.num_failed_subtasks(job) {
+                        0 => total.to_string(),
+                        failed => format!("{}, {} failed", total, failed),
+                    };
+                    columns[2].push(total_text.into());
+                    progresses.push(Some(current as f32 / total as f32));
                 }
             }
         }
+
+        if num_jobs > max {
+            num_rows += 1;
+            columns[0].push("...".into());
+        }
+
+        // Rightmost first, since that's the order they're dropped in
+        let timing_columns = [rate_column, eta_column, elapsed_column, queue_wait_column]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        let layout = fit_columns(
+            terminal_width,
+            &mut columns,
+            max_depth * 2,
+            &timing_columns,
+            progresses.iter().any(Option::is_some),
+        );
+
+        for row_index in 0..num_rows {
+            rows.push(draw_line(
+                terminal_width,
+                &mut columns,
+                &layout,
+                row_index,
+                progresses
+                    .get(row_index)
+                    .map(|e| e.to_owned())
+                    .unwrap_or_default(),
+                config,
+            ));
+            rows.extend(columns[0].continuation_lines(row_index));
+        }
+
+        Some(FrameSection {
+            title: match &section.title {
+                Some(title) => format!("{} ({}):", title, num_jobs),
+                None => format!("{} ({}):", status.name(), num_jobs),
+            },
+            rows,
+        })
     }
 }
 
+// The smallest progress bar worth drawing, e.g. "[=>]"
+const MIN_PROGRESS_BAR_WIDTH: usize = 4;
+
+// What to draw of each row in a section, so that they all line up.
+struct Layout {
+    visible_columns: Vec<bool>,
+    show_progress_bar: bool,
+}
+
+// Works out what fits within the terminal. Whatever matters least goes first: the
+// progress bars, then the timing columns (in the order given), then the subtask counts.
+// Columns are only ever dropped whole, never cut partway. While anything else is drawn,
+// the name keeps at least a grapheme alongside the ellipsis (after `indent` cells of
+// indentation), and it only loses its padding once there's nothing else left.
+fn fit_columns(
+    terminal_width: usize,
+    columns: &mut [Column],
+    indent: usize,
+    timing_columns: &[usize],
+    has_progress: bool,
+) -> Layout {
+    // Anything blank in every row would only ever be padding
+    let mut layout = Layout {
+        visible_columns: columns
+            .iter()
+            .map(|column| column.natural_width() > 0)
+            .collect(),
+        show_progress_bar: has_progress,
+    };
+    let min_name_width = columns[0].min_line_len(indent);
+    let name_width = columns[0].line_len().max(min_name_width);
+
+    // The subtask counts only make sense together
+    let mut drop_order = timing_columns
+        .iter()
+        .map(std::slice::from_ref)
+        .chain([&[1, 2][..]]);
+    while name_width + others_width(columns, &layout) > terminal_width {
+        if layout.show_progress_bar {
+            layout.show_progress_bar = false;
+        } else if let Some(dropped) = drop_order.next() {
+            for index in dropped {
+                layout.visible_columns[*index] = false;
+            }
+        } else {
+            break;
+        }
+    }
+
+    // Whatever's left is the name's
+    let room = terminal_width.saturating_sub(others_width(columns, &layout));
+    let name = &mut columns[0];
+    if room < min_name_width {
+        // Not even enough room for the padding, with nothing else left to drop
+        name.remove_padding();
+        name.refit(ColumnFit::EXACT(room));
+    } else if name.line_len() < min_name_width {
+        // `task_name_width` left it no room for anything but the ellipsis
+        name.refit(ColumnFit::EXACT(
+            room.min(name.natural_width() + name.padding()),
+        ));
+    } else if name.line_len() > room {
+        name.refit(ColumnFit::EXACT(room));
+    }
+
+    return layout;
+}
+
+// How much room everything but the name takes up.
+fn others_width(columns: &[Column], layout: &Layout) -> usize {
+    let columns_width = columns
+        .iter()
+        .zip(&layout.visible_columns)
+        .skip(1)
+        .filter(|(_, visible)| **visible)
+        .map(|(column, _)| column.line_len())
+        .sum::<usize>();
+    let progress_bar_width = match layout.show_progress_bar {
+        true => MIN_PROGRESS_BAR_WIDTH,
+        false => 0,
+    };
+    return columns_width + progress_bar_width;
+}
+
 fn draw_line(
     terminal_width: usize,
     columns: &mut [Column],
+    layout: &Layout,
     row_index: usize,
     maybe_progress: Option<f32>,
     config: &SBStateConfig,
 ) -> String {
     let mut line = String::new();
     let mut line_len = 0;
+    let visible_columns = columns
+        .iter_mut()
+        .zip(&layout.visible_columns)
+        .filter(|(_, visible)| **visible)
+        .map(|(column, _)| column);
 
     if let Some(progress) = maybe_progress {
-        for column_index in 0..columns.len() {
-            line_len += columns[column_index].line_len();
-            if line_len > terminal_width {
-                break;
-            }
-
-            line += &format!("{}", columns[column_index].to_string(row_index));
+        for column in visible_columns {
+            line_len += column.line_len();
+            line += &column.to_string(row_index);
         }
 
-        line += &get_progress_bar(
-            progress,
-            terminal_width.checked_sub(line_len).unwrap_or_default(),
-        );
+        if layout.show_progress_bar {
+            line += &get_progress_bar(progress, terminal_width.saturating_sub(line_len));
+        }
     } else {
-        let mut effective_columns = columns
-            .iter_mut()
+        let mut effective_columns = visible_columns
             .filter(|c| !c.is_empty(row_index))
             .collect::<Vec<_>>();
         let num_effective_columns = effective_columns.len();
         for (index, column) in effective_columns.iter_mut().enumerate() {
-            if index == num_effective_columns - 1 && config.grow_if_no_progress {
-                line += &format!(
-                    "{}",
-                    column.to_wide_string(
-                        row_index,
-                        terminal_width.checked_sub(line.len()).unwrap_or_default()
-                    )
+            if index + 1 == num_effective_columns && config.grow_if_no_progress {
+                line += &column.to_wide_string(
+                    row_index,
+                    terminal_width.saturating_sub(display_width(&line)),
                 );
             } else {
-                line += &format!("{}", column.to_string(row_index));
+                line += &column.to_string(row_index);
             }
         }
     }
@@ -276,8 +938,20 @@ fn draw_line(
     line
 }
 
+// Short, human readable durations like "4.2s", "3m07s" or "1h02m".
+pub(crate) fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    if seconds < 60 {
+        format!("{:.1}s", duration.as_secs_f32())
+    } else if seconds < 60 * 60 {
+        format!("{}m{:02}s", seconds / 60, seconds % 60)
+    } else {
+        format!("{}h{:02}m", seconds / (60 * 60), (seconds / 60) % 60)
+    }
+}
+
 fn get_progress_bar(progress: f32, available_width: usize) -> String {
-    if available_width < 4 {
+    if available_width < MIN_PROGRESS_BAR_WIDTH {
         return String::new();
     }
     let available_width = available_width - 2;
@@ -287,9 +961,8 @@ fn get_progress_bar(progress: f32, available_width: usize) -> String {
     let remaining_width = available_width - bar_width.max(1);
 
     format!(
-        "{}{}{}",
-        "[",
+        "[{}{:.>remaining_width$}",
         format!("{:=>bar_width$}", ">").bright_blue(),
-        format!("{:.>remaining_width$}", "]"),
+        "]",
     )
 }
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+// One task, as far as JUnit is concerned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TestCase {
+    pub name: String,
+
+    // Top-level tasks are their own class, and subtasks go
+    // under the path of whatever they're nested in, e.g. "build/compile".
+    pub classname: String,
+    pub time: Duration,
+    pub outcome: Outcome,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Outcome {
+    Passed,
+    Failed(String),
+
+    // Anything that didn't get to finish, along with why
+    Skipped(String),
+}
+
+// A JUnit XML report with every test case in a single suite.
+pub(crate) fn build_junit_report(cases: &[TestCase], wall_time: Duration) -> String {
+    let num_failures = cases
+        .iter()
+        .filter(|case| matches!(case.outcome, Outcome::Failed(_)))
+        .count();
+    let num_skipped = cases
+        .iter()
+        .filter(|case| matches!(case.outcome, Outcome::Skipped(_)))
+        .count();
+    let counts = format!(
+        r#"tests="{}" failures="{}" errors="0" skipped="{}" time="{:.3}""#,
+        cases.len(),
+        num_failures,
+        num_skipped,
+        wall_time.as_secs_f64()
+    );
+
+    let mut report = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    report += &format!("<testsuites {}>\n", counts);
+    report += &format!("  <testsuite name=\"cli-status-board\" {}>\n", counts);
+    for case in cases {
+        report += &format!(
+            "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+            escape(&case.name),
+            escape(&case.classname),
+            case.time.as_secs_f64()
+        );
+        report += &match &case.outcome {
+            Outcome::Passed => "/>\n".to_string(),
+            Outcome::Failed(message) => format!(
+                ">\n      <failure message=\"{}\"/>\n    </testcase>\n",
+                escape(message)
+            ),
+            Outcome::Skipped(message) => format!(
+                ">\n      <skipped message=\"{}\"/>\n    </testcase>\n",
+                escape(message)
+            ),
+        };
+    }
+    report += "  </testsuite>\n";
+    report += "</testsuites>\n";
+    report
+}
+
+// Makes some text safe to put in an attribute.
+fn escape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => result += "&amp;",
+            '<' => result += "&lt;",
+            '>' => result += "&gt;",
+            '"' => result += "&quot;",
+            '\'' => result += "&apos;",
+            '\t' => result += "&#9;",
+            '\n' => result += "&#10;",
+            '\r' => result += "&#13;",
+            // Not allowed in XML 1.0 at all, even escaped
+            c if c.is_control() => {}
+            c => result.push(c),
+        }
+    }
+    result
+}
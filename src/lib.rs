@@ -1,10 +1,38 @@
+#![allow(
+    clippy::needless_return,
+    clippy::upper_case_acronyms,
+    clippy::wrong_self_convention
+)]
+
 mod column;
+mod error;
+#[cfg(feature = "serde")]
+mod event_log;
 mod internal_state;
+mod junit;
+mod renderer;
+#[cfg(feature = "serde")]
+mod replay;
 mod state;
+mod status_index;
+mod status_registry;
+mod summary;
 mod task;
 mod task_id;
 
-pub use state::{SBState, SBStateConfig, TaskNameWidth};
+pub use colored::Color;
+pub use error::Error;
+pub use renderer::{
+    Frame, FrameSection, LogRenderer, MemoryRenderer, PlainTextRenderer, Renderer,
+    TerminalRenderer, Transition,
+};
+#[cfg(feature = "serde")]
+pub use replay::ReplaySpeed;
+pub use state::{
+    RenderMode, Retention, SBState, SBStateConfig, SectionConfig, TaskNameTruncation, TaskNameWidth,
+};
+pub use status_registry::{StatusDefinition, StatusRegistry};
+pub use summary::{Summary, TaskSummary};
 pub use task_id::TaskId;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -14,16 +42,125 @@ pub enum Status {
     Finished,
     Error,
     Info,
+
+    // Stopped early on purpose, never to finish
+    Cancelled,
+    // Deliberately not run at all
+    Skipped,
+    // Failed at least once, but is being tried again
+    Retrying,
+    // Waiting on something else before it can start
+    Blocked,
+    // Started, but temporarily on hold
+    Paused,
+    // Like Info and Error, a message rather than an actual task
+    Warning,
+
+    // Anything domain specific, like "Uploading" or "Awaiting review".
+    // These should be registered with `SBStateConfig::register_status` so that
+    // the status board knows how to list and count them. Anything unregistered
+    // is counted as unfinished, and listed after everything else.
+    Custom(&'static str),
 }
 
 impl Status {
-    fn is_finished(&self) -> bool {
+    // A custom status with a name that doesn't have to be `'static`,
+    // e.g. because it was read from a config file.
+    pub fn custom(name: &str) -> Status {
+        Status::Custom(status_registry::intern_name(name))
+    }
+
+    // How this status is referred to when displayed, e.g. in section titles.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Status::Queued => "Queued",
+            Status::Started => "Started",
+            Status::Finished => "Finished",
+            Status::Error => "Error",
+            Status::Info => "Info",
+            Status::Cancelled => "Cancelled",
+            Status::Skipped => "Skipped",
+            Status::Retrying => "Retrying",
+            Status::Blocked => "Blocked",
+            Status::Paused => "Paused",
+            Status::Warning => "Warning",
+            Status::Custom(name) => name,
+        }
+    }
+}
+
+// Built-in statuses are stored by name, e.g. "Queued". Custom ones are tagged,
+// e.g. {"Custom": "Uploading"}, so that they're never mistaken for a built-in one.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Status {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         match self {
-            Status::Queued => false,
-            Status::Started => false,
-            Status::Finished => true,
-            Status::Info => true,
-            Status::Error => true,
+            Status::Custom(name) => {
+                serializer.serialize_newtype_variant("Status", 11, "Custom", name)
+            }
+            _ => serializer.serialize_str(self.name()),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Status {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(StatusVisitor)
+    }
+}
+
+#[cfg(feature = "serde")]
+struct StatusVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Visitor<'de> for StatusVisitor {
+    type Value = Status;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "the name of a built-in status, or a tagged custom status"
+        )
+    }
+
+    fn visit_str<E: serde::de::Error>(self, name: &str) -> Result<Status, E> {
+        const BUILT_IN: &[&str] = &[
+            "Queued",
+            "Started",
+            "Finished",
+            "Error",
+            "Info",
+            "Cancelled",
+            "Skipped",
+            "Retrying",
+            "Blocked",
+            "Paused",
+            "Warning",
+        ];
+        Ok(match name {
+            "Queued" => Status::Queued,
+            "Started" => Status::Started,
+            "Finished" => Status::Finished,
+            "Error" => Status::Error,
+            "Info" => Status::Info,
+            "Cancelled" => Status::Cancelled,
+            "Skipped" => Status::Skipped,
+            "Retrying" => Status::Retrying,
+            "Blocked" => Status::Blocked,
+            "Paused" => Status::Paused,
+            "Warning" => Status::Warning,
+            _ => return Err(E::unknown_variant(name, BUILT_IN)),
+        })
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<Status, A::Error> {
+        use serde::de::Error;
+
+        match map.next_key::<String>()? {
+            Some(tag) if tag == "Custom" => Ok(Status::custom(&map.next_value::<String>()?)),
+            Some(tag) => Err(A::Error::unknown_variant(&tag, &["Custom"])),
+            None => Err(A::Error::invalid_length(0, &self)),
         }
     }
 }
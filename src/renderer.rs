@@ -0,0 +1,360 @@
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{RenderMode, Status};
+
+// A single snapshot of the status board, laid out for a specific width.
+// Rows are already padded/truncated to fit and may contain ANSI color codes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub width: usize,
+    pub header: String,
+    pub sections: Vec<FrameSection>,
+    pub num_finished: usize,
+    pub num_total: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameSection {
+    pub title: String,
+    pub rows: Vec<String>,
+}
+
+// A top-level task was either added (no previous status) or changed status.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transition {
+    pub name: String,
+    pub previous_status: Option<Status>,
+    pub status: Status,
+}
+
+impl Frame {
+    // Every line of this frame, in the order it should be displayed.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![self.header.clone()];
+        for section in &self.sections {
+            lines.push(String::new());
+            lines.push(section.title.clone());
+            lines.extend(section.rows.iter().cloned());
+        }
+        lines
+    }
+
+    // Same as `lines`, but with all of the coloring stripped out.
+    pub fn plain_lines(&self) -> Vec<String> {
+        self.lines().iter().map(|line| strip_ansi(line)).collect()
+    }
+
+    pub fn to_plain_string(&self) -> String {
+        self.plain_lines().join("\n")
+    }
+}
+
+// Something that can display frames of the status board.
+// Called exclusively from the render thread.
+pub trait Renderer: Send {
+    // The available (width, height), in characters.
+    // If this returns None then nothing will be rendered.
+    fn size(&self) -> Option<(usize, usize)>;
+
+    fn render(&mut self, frame: &Frame);
+
+    // Called as soon as a task is added or changes status, before the next frame is rendered.
+    fn transition(&mut self, _transition: &Transition) {}
+
+    // Called on every refresh, whether or not there was anything new to render,
+    // for anything that should happen on a timer.
+    fn tick(&mut self) {}
+
+    // Called after the final frame with anything worth keeping around
+    // once the board is gone (e.g. a recap of every error, or a summary).
+    fn report(&mut self, _report: &str) {}
+
+    // Called once after the final frame (and any report) has been rendered.
+    fn finish(&mut self) {}
+}
+
+// Draws straight to the terminal, either taking over the whole screen
+// or living inline below the rest of the program's output.
+#[derive(Debug)]
+pub struct TerminalRenderer {
+    render_mode: RenderMode,
+    num_drawn_lines: usize,
+    hid_cursor: bool,
+}
+
+impl TerminalRenderer {
+    pub fn new(render_mode: RenderMode) -> Self {
+        Self {
+            render_mode,
+            num_drawn_lines: 0,
+            hid_cursor: false,
+        }
+    }
+}
+
+impl Renderer for TerminalRenderer {
+    fn size(&self) -> Option<(usize, usize)> {
+        termion::terminal_size()
+            .ok()
+            .map(|(width, height)| (width as usize, height as usize))
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        let height = self.size().map(|(_, height)| height).unwrap_or_default();
+        let lines = frame.lines();
+
+        let mut stdout = std::io::stdout().lock();
+        if !self.hid_cursor {
+            let _ = write!(stdout, "{}", termion::cursor::Hide);
+            self.hid_cursor = true;
+        }
+
+        let lines = match self.render_mode {
+            RenderMode::Fullscreen => {
+                // Reset the display
+                let _ = write!(
+                    stdout,
+                    "{}{}",
+                    termion::clear::All,
+                    termion::cursor::Goto(0, 1)
+                );
+                &lines[..]
+            }
+            RenderMode::Inline => {
+                // Only wipe the lines we drew last time, leaving everything above alone.
+                // We can't move the cursor past the top of the screen, so leave a line
+                // of headroom and drop whatever doesn't fit.
+                if self.num_drawn_lines > 0 {
+                    let _ = write!(
+                        stdout,
+                        "\r{}",
+                        termion::cursor::Up(self.num_drawn_lines as u16)
+                    );
+                }
+                let _ = write!(stdout, "{}", termion::clear::AfterCursor);
+                &lines[..lines.len().min(height.saturating_sub(1))]
+            }
+        };
+
+        for line in lines {
+            let _ = writeln!(stdout, "{}", line);
+        }
+        let _ = stdout.flush();
+
+        self.num_drawn_lines = lines.len();
+    }
+
+    fn report(&mut self, report: &str) {
+        println!("\n{}", report);
+    }
+
+    fn finish(&mut self) {
+        if self.hid_cursor {
+            print!("{}", termion::cursor::Show);
+            let _ = std::io::stdout().flush();
+        }
+    }
+}
+
+// Writes every frame, uncolored, to some writer. Frames are separated by a blank line.
+#[derive(Debug)]
+pub struct PlainTextRenderer<W: Write + Send> {
+    writer: W,
+    width: usize,
+}
+
+impl<W: Write + Send> PlainTextRenderer<W> {
+    pub fn new(writer: W, width: usize) -> Self {
+        Self { writer, width }
+    }
+}
+
+impl<W: Write + Send> Renderer for PlainTextRenderer<W> {
+    fn size(&self) -> Option<(usize, usize)> {
+        Some((self.width, usize::MAX))
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        for line in frame.plain_lines() {
+            let _ = writeln!(self.writer, "{}", line);
+        }
+        let _ = writeln!(self.writer);
+    }
+
+    fn report(&mut self, report: &str) {
+        let _ = writeln!(self.writer, "{}", strip_ansi(report));
+    }
+
+    fn finish(&mut self) {
+        let _ = self.writer.flush();
+    }
+}
+
+// For when there's no terminal to draw to (e.g. when piped in CI).
+// Writes one timestamped line per transition instead of drawing frames,
+// plus a summary of the finished tasks every so often.
+#[derive(Debug)]
+pub struct LogRenderer<W: Write + Send> {
+    writer: W,
+    start: Instant,
+    summary_interval: Duration,
+    last_summary_time: Instant,
+    summarized_counts: Option<(usize, usize)>,
+    latest_counts: (usize, usize),
+}
+
+impl<W: Write + Send> LogRenderer<W> {
+    pub fn new(writer: W, summary_interval: Duration) -> Self {
+        Self {
+            writer,
+            start: Instant::now(),
+            summary_interval,
+            last_summary_time: Instant::now(),
+            summarized_counts: None,
+            latest_counts: (0, 0),
+        }
+    }
+
+    fn timestamp(&self) -> String {
+        format!("[{:>9.3}s]", self.start.elapsed().as_secs_f64())
+    }
+
+    // Only bother summarizing when something actually changed.
+    fn maybe_write_summary(&mut self) {
+        if self.summarized_counts == Some(self.latest_counts) {
+            return;
+        }
+
+        let (num_finished, num_total) = self.latest_counts;
+        let _ = writeln!(
+            self.writer,
+            "{} Finished tasks: {} / {}",
+            self.timestamp(),
+            num_finished,
+            num_total
+        );
+        self.last_summary_time = Instant::now();
+        self.summarized_counts = Some(self.latest_counts);
+    }
+}
+
+impl<W: Write + Send> Renderer for LogRenderer<W> {
+    fn size(&self) -> Option<(usize, usize)> {
+        // Nothing here depends on the size, so just pick something reasonable.
+        Some((80, usize::MAX))
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        self.latest_counts = (frame.num_finished, frame.num_total);
+    }
+
+    // Frames are only rendered when something happens, so this is what
+    // keeps the summary going even when nothing has in a while.
+    fn tick(&mut self) {
+        if self.last_summary_time.elapsed() >= self.summary_interval {
+            self.maybe_write_summary();
+        }
+        let _ = self.writer.flush();
+    }
+
+    fn transition(&mut self, transition: &Transition) {
+        let _ = writeln!(
+            self.writer,
+            "{} {}: {}",
+            self.timestamp(),
+            transition.status.name(),
+            transition.name
+        );
+    }
+
+    fn report(&mut self, report: &str) {
+        let _ = writeln!(self.writer, "{}", strip_ansi(report));
+    }
+
+    fn finish(&mut self) {
+        self.maybe_write_summary();
+        let _ = self.writer.flush();
+    }
+}
+
+// Keeps every rendered frame in memory, at a fixed width.
+// Clones share the same frames, so keep one around to inspect what was
+// rendered after handing the other to the status board. Mostly useful for tests.
+#[derive(Debug, Clone)]
+pub struct MemoryRenderer {
+    width: usize,
+    frames: Arc<Mutex<Vec<Frame>>>,
+    reports: Arc<Mutex<Vec<String>>>,
+}
+
+impl MemoryRenderer {
+    pub fn new(width: usize) -> Self {
+        Self {
+            width,
+            frames: Arc::default(),
+            reports: Arc::default(),
+        }
+    }
+
+    pub fn frames(&self) -> Vec<Frame> {
+        match self.frames.lock() {
+            Ok(frames) => frames.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    pub fn last_frame(&self) -> Option<Frame> {
+        self.frames().pop()
+    }
+
+    // Every report, uncolored.
+    pub fn reports(&self) -> Vec<String> {
+        match self.reports.lock() {
+            Ok(reports) => reports.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+}
+
+impl Renderer for MemoryRenderer {
+    fn size(&self) -> Option<(usize, usize)> {
+        Some((self.width, usize::MAX))
+    }
+
+    fn render(&mut self, frame: &Frame) {
+        match self.frames.lock() {
+            Ok(mut frames) => frames.push(frame.clone()),
+            Err(poisoned) => poisoned.into_inner().push(frame.clone()),
+        }
+    }
+
+    fn report(&mut self, report: &str) {
+        match self.reports.lock() {
+            Ok(mut reports) => reports.push(strip_ansi(report)),
+            Err(poisoned) => poisoned.into_inner().push(strip_ansi(report)),
+        }
+    }
+}
+
+// Removes any ANSI escape sequences (i.e. colors) from a string.
+pub(crate) fn strip_ansi(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip the "[", any parameters, and the final letter.
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
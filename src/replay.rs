@@ -0,0 +1,92 @@
+use std::{
+    io::BufRead,
+    time::{Duration, Instant},
+};
+
+use rustc_hash::FxHashMap;
+
+use crate::{Error, SBState, TaskId, event_log::LoggedEvent, state::TaskEvent};
+
+// How quickly to play back a recorded event log.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplaySpeed {
+    // As it originally happened
+    Original,
+
+    // Some multiple of the original speed, e.g. 2.0 is twice as fast.
+    // Must be positive, since time can't go backwards (or stand still).
+    Scaled(f64),
+
+    // Everything at once
+    Instant,
+}
+
+impl SBState {
+    // Feeds every event in a JSON Lines log (see `SBStateConfig::event_log_path`)
+    // into this status board, blocking until they've all been sent.
+    // Each recorded task gets a fresh id, so this can share a board with other tasks.
+    // Any recorded shutdown is ignored, so the board keeps running afterwards.
+    pub fn replay<R: BufRead>(&self, log: R, speed: ReplaySpeed) -> Result<(), Error> {
+        if let ReplaySpeed::Scaled(factor) = speed
+            && (factor.is_nan() || factor <= 0.0)
+        {
+            return Err(Error::InvalidReplaySpeed);
+        }
+
+        let start = Instant::now();
+        let mut ids = FxHashMap::default();
+
+        for (index, line) in log.lines().enumerate() {
+            let line = line.map_err(|err| Error::Io(err.kind()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let logged: LoggedEvent<TaskEvent> =
+                serde_json::from_str(&line).map_err(|_| Error::InvalidEventLog(index + 1))?;
+            let time = Duration::try_from_secs_f64(logged.time).unwrap_or_default();
+            let time = match speed {
+                ReplaySpeed::Original => Some(time),
+                ReplaySpeed::Scaled(factor) => Some(
+                    Duration::try_from_secs_f64(time.as_secs_f64() / factor)
+                        .unwrap_or(Duration::MAX),
+                ),
+                ReplaySpeed::Instant => None,
+            };
+            if let Some(time) = time {
+                std::thread::sleep(time.saturating_sub(start.elapsed()));
+            }
+
+            if let Some(event) = remap(logged.event, &mut ids) {
+                self.send(event)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Swaps every recorded id for one that's unique to this replay.
+fn remap(event: TaskEvent, ids: &mut FxHashMap<i32, TaskId>) -> Option<TaskEvent> {
+    // These are all weak, so dropping them doesn't finish anything
+    let mut id = |key: TaskId| ids.entry(key.id).or_default().make_weak();
+
+    Some(match event {
+        TaskEvent::AddTask(key, name, status) => TaskEvent::AddTask(id(key), name, status),
+        TaskEvent::SetTaskDisplayName(key, name) => TaskEvent::SetTaskDisplayName(id(key), name),
+        TaskEvent::UpdateTask(key, status) => TaskEvent::UpdateTask(id(key), status),
+        TaskEvent::DeleteTask(key) => TaskEvent::DeleteTask(id(key)),
+        TaskEvent::AddSubTask(key, subkey, name, status) => {
+            TaskEvent::AddSubTask(id(key), id(subkey), name, status)
+        }
+        TaskEvent::UpdateSubTask(key, subkey, status) => {
+            TaskEvent::UpdateSubTask(id(key), id(subkey), status)
+        }
+        TaskEvent::SetProgress(key, current, total) => {
+            TaskEvent::SetProgress(id(key), current, total)
+        }
+        TaskEvent::IncProgress(key, amount) => TaskEvent::IncProgress(id(key), amount),
+        TaskEvent::FinishOnDrop(key, status) => TaskEvent::FinishOnDrop(id(key), status),
+        TaskEvent::Summarize(_) | TaskEvent::ExportJUnit(_) | TaskEvent::Shutdown => return None,
+    })
+}
@@ -1,10 +1,35 @@
-use crate::{Status, TaskId, column::ColumnFit, internal_state::InternalState};
-use colored::Colorize;
-use std::{sync::mpsc::Sender, time::Duration};
+#[cfg(feature = "serde")]
+use crate::event_log::EventLog;
+use crate::{
+    Error, Status, StatusDefinition, StatusRegistry, Summary, TaskId,
+    column::{ColumnFit, clamp_width},
+    internal_state::InternalState,
+    renderer::{Frame, FrameSection, LogRenderer, Renderer, TerminalRenderer},
+};
+use colored::{Color, Colorize};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, mpsc::Sender},
+    thread::JoinHandle,
+    time::Duration,
+};
 
 #[derive(Debug, Clone)]
 pub struct SBState {
     sender: Sender<TaskEvent>,
+    render_thread: Arc<RenderThread>,
+    error_on_panic: bool,
+}
+
+// Owns the render thread. Shared between every clone of an `SBState` so
+// that the board shuts down once the last one goes away.
+#[derive(Debug)]
+struct RenderThread {
+    sender: Sender<TaskEvent>,
+    handle: Mutex<Option<JoinHandle<Summary>>>,
+
+    // What the render thread handed back once it finished
+    final_summary: Mutex<Option<Summary>>,
 }
 
 // Configuration for the status board.
@@ -14,6 +39,13 @@ pub struct SBStateConfig {
     // If unset then we'll restrict it to 50% of the available screen.
     pub task_name_width: TaskNameWidth,
 
+    // Which part of task names that are too long to cut.
+    // Defaults to cutting off the end.
+    pub task_name_truncation: TaskNameTruncation,
+
+    // What to replace whatever was cut with. Defaults to "..."
+    pub ellipsis: String,
+
     // Custom refresh rate for the status board. Defaults to 30 ms.
     // Since this only actually rerenders when the terminal size has
     // changed or there's a pending event this tends to be fine, but
@@ -27,6 +59,90 @@ pub struct SBStateConfig {
     // In the case that a line doesn't have any progress, should we grow it?
     // Default to true
     pub grow_if_no_progress: bool,
+
+    // If a task/subtask handle drops while its thread is panicking, mark it as
+    // an error instead of finished. Default to false
+    pub error_on_panic: bool,
+
+    // How many levels of unfinished subtasks to draw underneath each task,
+    // indented to show the tree. Default to 0, i.e. only draw the running
+    // subtasks that were given a name, just below their top-level task.
+    pub subtask_depth: usize,
+
+    // At most how many subtasks to draw underneath any one task.
+    // Set to 0 to never draw any. Default to 3
+    pub max_subtasks_shown: usize,
+
+    // Also draw subtasks that are still queued, not just the ones that
+    // have started. Default to false
+    pub show_queued_subtasks: bool,
+
+    // Show how long each task spent queued before it started,
+    // or how long it's been waiting so far. Default to false
+    pub show_queue_wait: bool,
+
+    // Show how long each running task (i.e. anything unfinished that isn't queued,
+    // including custom statuses) has been running for, including any time spent
+    // retrying or paused since it first started.
+    // Default to false
+    pub show_elapsed: bool,
+
+    // Show an estimate of how much longer each running task will take,
+    // based on how quickly its progress/subtasks have been completing so far.
+    // Default to false
+    pub show_eta: bool,
+
+    // Show how many items per second each running task is getting through.
+    // Default to false
+    pub show_rate: bool,
+
+    // How the status board should share the terminal with everything else.
+    // Defaults to taking over the whole screen.
+    pub render_mode: RenderMode,
+
+    // Every status tasks can have, and how to count them.
+    // Includes all of the built-in statuses by default. See `register_status`.
+    pub statuses: StatusRegistry,
+
+    // Which statuses to list, in what order, and how.
+    // By default messages come first, followed by unfinished and errored tasks.
+    // Finished, cancelled and skipped tasks are included but hidden.
+    pub sections: Vec<SectionConfig>,
+
+    // How many finished (or cancelled/skipped/custom finished) tasks to hold on to. Even once
+    // they're dropped they still count towards the totals, so this just bounds memory usage.
+    // Defaults to keeping everything.
+    pub finished_retention: Retention,
+
+    // How long errors and info/warning messages stick around for.
+    // Both default to 10 seconds.
+    // Any of these three can be overridden for a single status with `SectionConfig::retention`.
+    pub error_retention: Retention,
+    pub info_retention: Retention,
+
+    // Once the board shuts down, list every error that happened,
+    // including the ones that are no longer shown. Default to false
+    pub error_recap: bool,
+
+    // Once the board shuts down, print a summary of the whole run. Default to false
+    pub summary_on_shutdown: bool,
+
+    // How many of the slowest tasks to include in summaries. Default to 5
+    pub summary_slowest_tasks: usize,
+
+    // Once the board shuts down, write every task to this file as a JUnit XML report.
+    // Default to None
+    pub junit_path: Option<PathBuf>,
+
+    // Record every event, along with when it happened, to this file as JSON Lines.
+    // Default to None
+    #[cfg(feature = "serde")]
+    pub event_log_path: Option<PathBuf>,
+
+    // When stdout isn't a terminal we log each status change instead of drawing
+    // the board. This is how often to also log how many tasks have finished so far.
+    // Defaults to 10 seconds.
+    pub log_summary_interval: Duration,
 }
 
 #[derive(Debug, Clone)]
@@ -44,19 +160,227 @@ pub enum TaskNameWidth {
     ExactChars(usize),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskNameTruncation {
+    // Keep the start, e.g. "crates/foo/s..."
+    End,
+
+    // Keep the end, e.g. "...deep/module.rs"
+    Start,
+
+    // Keep both ends, e.g. "crates/...module.rs"
+    Middle,
+
+    // For file paths: drop whole leading directories, e.g. ".../deep/module.rs".
+    // Anything without directories is cut at the end instead.
+    Path,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderMode {
+    // Clear the whole screen and redraw from the top on every refresh
+    Fullscreen,
+
+    // Only redraw the lines owned by the status board, just below whatever
+    // was printed before it started
+    Inline,
+}
+
+// How to list all of the top-level tasks with a given status.
+#[derive(Debug, Clone)]
+pub struct SectionConfig {
+    pub status: Status,
+
+    // If false then this section isn't drawn at all
+    pub visible: bool,
+
+    // Defaults to the name of the status
+    pub title: Option<String>,
+
+    // What color to draw task names in, if any
+    pub color: Option<Color>,
+
+    // At most how many tasks to list, not counting subtasks
+    pub max_rows: usize,
+
+    // List the tasks that most recently got this status first, rather than the oldest
+    pub newest_first: bool,
+
+    // Let task names take up the whole line, ignoring `task_name_width`.
+    // Meant for messages, which don't have any progress.
+    pub full_width: bool,
+
+    // Wrap task names that are too long onto extra, indented lines instead of cutting them
+    // short. Any progress bar stays on the first line. Default to false
+    pub wrap: bool,
+
+    // How long to keep tasks with this status around, e.g. to keep warnings for longer
+    // than info messages. Only applies to finished statuses (messages included).
+    // Defaults to `finished_retention`, `error_retention` or `info_retention`.
+    pub retention: Option<Retention>,
+}
+
+impl SectionConfig {
+    // A visible section listing the 10 oldest tasks with this status.
+    pub fn new(status: Status, color: Option<Color>) -> Self {
+        Self {
+            status,
+            visible: true,
+            title: None,
+            color,
+            max_rows: 10,
+            newest_first: false,
+            full_width: false,
+            wrap: false,
+            retention: None,
+        }
+    }
+}
+
+// Limits on how many tasks with some status to keep around, dropping the oldest first.
+// If neither is set then everything is kept forever.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Retention {
+    // Keep at most this many
+    pub max_count: Option<usize>,
+
+    // Drop anything that's had this status for longer than this
+    pub max_age: Option<Duration>,
+}
+
+impl Retention {
+    pub fn keep_forever() -> Self {
+        Self::default()
+    }
+
+    pub fn keep_last(max_count: usize) -> Self {
+        Self {
+            max_count: Some(max_count),
+            max_age: None,
+        }
+    }
+
+    pub fn keep_for(max_age: Duration) -> Self {
+        Self {
+            max_count: None,
+            max_age: Some(max_age),
+        }
+    }
+}
+
 impl Default for SBStateConfig {
     fn default() -> Self {
         Self {
             silent: false,
             refresh_rate: Duration::from_millis(30),
             task_name_width: TaskNameWidth::Max(0.5),
+            task_name_truncation: TaskNameTruncation::End,
+            ellipsis: "...".to_string(),
             grow_if_no_progress: true,
+            error_on_panic: false,
+            subtask_depth: 0,
+            max_subtasks_shown: 3,
+            show_queued_subtasks: false,
+            show_queue_wait: false,
+            show_elapsed: false,
+            show_eta: false,
+            show_rate: false,
+            render_mode: RenderMode::Fullscreen,
+            log_summary_interval: Duration::from_secs(10),
+            statuses: StatusRegistry::default(),
+            sections: vec![
+                SectionConfig {
+                    full_width: true,
+                    ..SectionConfig::new(Status::Info, None)
+                },
+                SectionConfig {
+                    full_width: true,
+                    ..SectionConfig::new(Status::Warning, Some(Color::Yellow))
+                },
+                SectionConfig::new(Status::Started, Some(Color::BrightGreen)),
+                SectionConfig::new(Status::Retrying, Some(Color::BrightMagenta)),
+                SectionConfig::new(Status::Blocked, Some(Color::Red)),
+                SectionConfig::new(Status::Paused, Some(Color::Cyan)),
+                SectionConfig::new(Status::Queued, Some(Color::BrightYellow)),
+                SectionConfig::new(Status::Error, Some(Color::BrightRed)),
+                SectionConfig {
+                    visible: false,
+                    newest_first: true,
+                    ..SectionConfig::new(Status::Finished, Some(Color::Green))
+                },
+                SectionConfig {
+                    visible: false,
+                    newest_first: true,
+                    ..SectionConfig::new(Status::Cancelled, Some(Color::BrightBlack))
+                },
+                SectionConfig {
+                    visible: false,
+                    newest_first: true,
+                    ..SectionConfig::new(Status::Skipped, Some(Color::BrightBlack))
+                },
+            ],
+            finished_retention: Retention::default(),
+            error_retention: Retention::keep_for(Duration::from_secs(10)),
+            info_retention: Retention::keep_for(Duration::from_secs(10)),
+            error_recap: false,
+            summary_on_shutdown: false,
+            summary_slowest_tasks: 5,
+            junit_path: None,
+            #[cfg(feature = "serde")]
+            event_log_path: None,
+        }
+    }
+}
+
+impl SBStateConfig {
+    // Adds a domain specific status (e.g. "Uploading"), listed in its own section
+    // at `section_index` within `sections`. Finished ones count towards the
+    // number of finished tasks and, like the built-in finished statuses, are hidden by default.
+    pub fn register_status(
+        &mut self,
+        name: &str,
+        color: Option<Color>,
+        section_index: usize,
+        is_finished: bool,
+    ) -> Status {
+        let status = Status::custom(name);
+        self.statuses.register(status, is_finished);
+        self.sections.retain(|section| section.status != status);
+        self.sections.insert(
+            section_index.min(self.sections.len()),
+            SectionConfig {
+                visible: !is_finished,
+                newest_first: is_finished,
+                ..SectionConfig::new(status, color)
+            },
+        );
+        return status;
+    }
+
+    // How long to keep tasks with this status around. Unfinished tasks are never dropped.
+    fn retention(&self, definition: &StatusDefinition) -> Option<Retention> {
+        if !definition.is_finished {
+            return None;
         }
+
+        let section_retention = self
+            .sections
+            .iter()
+            .find(|section| section.status == definition.status)
+            .and_then(|section| section.retention);
+        return Some(section_retention.unwrap_or(match definition {
+            d if d.is_message && d.is_failure => self.error_retention,
+            d if d.is_message => self.info_retention,
+            _ => self.finished_retention,
+        }));
     }
 }
 
 // Used internally to pipe commands over an mpsc channel.
-#[derive(Debug, PartialEq, Eq, Clone)]
+// Any TaskIds sent over should be weak, so that the last handle to a task
+// is always dropped (and so finishes the task) on its owner's thread.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) enum TaskEvent {
     AddTask(TaskId, Option<String>, Status),
     SetTaskDisplayName(TaskId, String),
@@ -64,48 +388,144 @@ pub(crate) enum TaskEvent {
     DeleteTask(TaskId),
     AddSubTask(TaskId, TaskId, Option<String>, Status),
     UpdateSubTask(TaskId, TaskId, Status),
+    SetProgress(TaskId, u64, u64),
+    IncProgress(TaskId, u64),
+    // The last handle to a task (or subtask) dropped, so unless it already
+    // finished some other way it's done, one way or another
+    FinishOnDrop(TaskId, Status),
+    // Queries only, so never worth recording
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Summarize(Sender<Summary>),
+    #[cfg_attr(feature = "serde", serde(skip))]
+    ExportJUnit(Sender<String>),
+    Shutdown,
 }
 
 impl SBState {
     pub fn new(config: SBStateConfig) -> Self {
+        let stdout = std::io::stdout();
+        if !termion::is_tty(&stdout) {
+            // Nobody's going to see the board (e.g. when piped in CI), so log instead.
+            let summary_interval = config.log_summary_interval;
+            Self::with_renderer(config, LogRenderer::new(stdout, summary_interval))
+        } else {
+            let render_mode = config.render_mode;
+            Self::with_renderer(config, TerminalRenderer::new(render_mode))
+        }
+    }
+
+    // Like `new`, but draws with a custom renderer instead of straight to the terminal.
+    pub fn with_renderer<R: Renderer + 'static>(config: SBStateConfig, mut renderer: R) -> Self {
         let (sender, receiver) = std::sync::mpsc::channel::<TaskEvent>();
+        let error_on_panic = config.error_on_panic;
 
-        std::thread::spawn(move || -> ! {
-            let mut internal_state = InternalState::default();
+        let handle = std::thread::spawn(move || {
+            let mut internal_state = InternalState::new(config.statuses.clone());
             let mut should_refresh_display = true;
             let mut old_width = 0;
             let mut old_height = 0;
+
+            #[cfg(feature = "serde")]
+            let (mut event_log, mut event_log_error) = match &config.event_log_path {
+                Some(path) => match EventLog::create(path) {
+                    Ok(event_log) => (Some(event_log), None),
+                    Err(err) => (None, Some(err)),
+                },
+                None => (None, None),
+            };
+
             loop {
+                let mut should_shutdown = false;
                 for event in receiver.try_iter() {
                     should_refresh_display = true;
+
+                    // Give up on the log as soon as anything goes wrong, rather than leave gaps
+                    #[cfg(feature = "serde")]
+                    if let Some(log) = &mut event_log
+                        && let Err(err) = log.record(&event)
+                    {
+                        event_log = None;
+                        event_log_error = Some(err);
+                    }
+
                     match event {
                         TaskEvent::AddTask(key, maybe_display_name, status) => {
-                            internal_state.add_task(key.make_weak(), maybe_display_name, status);
+                            let transition =
+                                internal_state.add_task(key, maybe_display_name, status);
+                            if !config.silent {
+                                renderer.transition(&transition);
+                            }
                         }
                         TaskEvent::SetTaskDisplayName(key, display_name) => {
-                            internal_state.set_display_name(key.make_weak(), display_name);
+                            internal_state.set_display_name(key, display_name);
                         }
                         TaskEvent::UpdateTask(key, status) => {
-                            internal_state.update_task(key.make_weak(), status);
+                            let maybe_transition = internal_state.update_task(key, status);
+                            if let Some(transition) = maybe_transition
+                                && !config.silent
+                            {
+                                renderer.transition(&transition);
+                            }
                         }
                         TaskEvent::DeleteTask(key) => {
-                            internal_state.delete_task(key.make_weak());
+                            internal_state.delete_task(key);
                         }
                         TaskEvent::AddSubTask(key, subkey, maybe_display_name, status) => {
-                            internal_state.add_subtask(
-                                key.make_weak(),
-                                subkey,
-                                maybe_display_name,
-                                status,
-                            );
+                            internal_state.add_subtask(key, subkey, maybe_display_name, status);
                         }
                         TaskEvent::UpdateSubTask(key, subkey, new_status) => {
-                            internal_state.update_subtask(key.make_weak(), subkey, new_status);
+                            internal_state.update_subtask(key, subkey, new_status);
+                        }
+                        TaskEvent::SetProgress(key, current, total) => {
+                            internal_state.set_progress(key, current, total);
+                        }
+                        TaskEvent::IncProgress(key, amount) => {
+                            internal_state.inc_progress(key, amount);
+                        }
+                        TaskEvent::FinishOnDrop(key, status) => {
+                            let maybe_transition = internal_state.finish_on_drop(key, status);
+                            if let Some(transition) = maybe_transition
+                                && !config.silent
+                            {
+                                renderer.transition(&transition);
+                            }
                         }
+                        TaskEvent::Summarize(sender) => {
+                            let _ =
+                                sender.send(internal_state.summary(config.summary_slowest_tasks));
+                        }
+                        TaskEvent::ExportJUnit(sender) => {
+                            let _ = sender.send(internal_state.junit_report());
+                        }
+                        TaskEvent::Shutdown => {
+                            // Keep draining, so that anything sent before shutting down
+                            // still makes it into the final frame.
+                            should_shutdown = true;
+                        }
+                    }
+                }
+
+                for definition in config.statuses.iter() {
+                    let Some(retention) = config.retention(definition) else {
+                        continue;
+                    };
+                    // Messages are actually shown, so redraw once any of them go away
+                    if internal_state.apply_retention(definition.status, &retention)
+                        && definition.is_message
+                    {
+                        should_refresh_display = true;
                     }
                 }
 
-                if let Ok((width, height)) = termion::terminal_size() {
+                #[cfg(feature = "serde")]
+                if let Some(log) = &mut event_log
+                    && let Err(err) = log.flush()
+                {
+                    event_log = None;
+                    event_log_error = Some(err);
+                }
+
+                if let Some((width, height)) = renderer.size() {
                     if width != old_width || height != old_height {
                         old_height = height;
                         old_width = width;
@@ -113,155 +533,408 @@ impl SBState {
                     }
 
                     if !config.silent && should_refresh_display {
-                        // Reset the display
-                        print!("{}", termion::clear::All);
-                        print!("{}", termion::cursor::Goto(0, 1));
-
-                        internal_state.clear_old_entries(
-                            std::time::Duration::from_secs(10),
-                            &[Status::Error, Status::Info],
-                        );
-
-                        let num_finished = match internal_state.task_map.get(&Status::Finished) {
-                            Some(v) => v.len(),
-                            None => 0,
-                        };
-
-                        println!(
-                            "Finished tasks: {} / {}",
-                            format!("{}", num_finished).bright_green(),
-                            internal_state.get_total(),
-                        );
-
-                        let width = width as usize;
-                        let task_name_fit = match config.task_name_width {
-                            TaskNameWidth::Min(max) => {
-                                ColumnFit::MIN((max.min(1.0) * width as f32) as usize)
-                            }
-                            TaskNameWidth::Max(max) => {
-                                ColumnFit::MAX((max.min(1.0) * width as f32) as usize)
-                            }
-                            TaskNameWidth::ExactRatio(max) => {
-                                ColumnFit::EXACT((max.min(1.0) * width as f32) as usize)
-                            }
-                            TaskNameWidth::ExactChars(max) => ColumnFit::EXACT(max.min(width)),
-                        };
-
-                        internal_state.print_list(
-                            Status::Info,
-                            10,
-                            |f: &str| f.into(),
-                            width,
-                            ColumnFit::EXACT(width),
-                            &config,
-                        );
-                        internal_state.print_list(
-                            Status::Started,
-                            10,
-                            |f: &str| f.bright_green(),
-                            width,
-                            task_name_fit,
-                            &config,
-                        );
-                        internal_state.print_list(
-                            Status::Queued,
-                            10,
-                            |f: &str| f.bright_yellow(),
-                            width,
-                            task_name_fit,
-                            &config,
-                        );
-                        internal_state.print_list(
-                            Status::Error,
-                            10,
-                            |f: &str| f.bright_red(),
-                            width,
-                            task_name_fit,
-                            &config,
-                        );
+                        renderer.render(&build_frame(&mut internal_state, width, &config));
                     }
                 }
 
+                if !config.silent {
+                    renderer.tick();
+                }
+
+                if should_shutdown {
+                    break;
+                }
+
                 std::thread::sleep(config.refresh_rate);
                 should_refresh_display = false;
             }
+
+            if config.error_recap && !config.silent {
+                let errors = internal_state.all_errors();
+                if !errors.is_empty() {
+                    renderer.report(&build_error_recap(&errors));
+                }
+            }
+
+            if let Some(path) = &config.junit_path
+                && let Err(err) = std::fs::write(path, internal_state.junit_report())
+                && !config.silent
+            {
+                renderer.report(&format!(
+                    "Couldn't write the JUnit report to {}: {}",
+                    path.display(),
+                    err
+                ));
+            }
+
+            #[cfg(feature = "serde")]
+            if let (Some(path), Some(err)) = (&config.event_log_path, event_log_error)
+                && !config.silent
+            {
+                renderer.report(&format!(
+                    "Couldn't write the event log to {}: {}",
+                    path.display(),
+                    err
+                ));
+            }
+
+            let summary = internal_state.summary(config.summary_slowest_tasks);
+            if config.summary_on_shutdown && !config.silent {
+                renderer.report(&summary.to_string());
+            }
+
+            renderer.finish();
+            summary
         });
 
-        Self { sender }
+        Self {
+            sender: sender.clone(),
+            render_thread: Arc::new(RenderThread {
+                sender,
+                handle: Mutex::new(Some(handle)),
+                final_summary: Mutex::new(None),
+            }),
+            error_on_panic,
+        }
+    }
+
+    // Stops the status board after applying and rendering every pending event,
+    // then restores the cursor. Any further updates are ignored.
+    // This happens automatically once the last clone of this state drops.
+    pub fn shutdown(&self) {
+        let _ = self.try_shutdown();
+    }
+
+    // Like `shutdown`, but reports whether the render thread had crashed.
+    pub fn try_shutdown(&self) -> Result<(), Error> {
+        self.render_thread.shutdown()
     }
 
+    // All of the following never panic. If the status board is no longer
+    // running then they do nothing, and any returned TaskId is inert.
+    // Use the `try_` variants to find out when that happens.
+
     pub fn error<S: ToString>(&self, display_name: S) {
-        let task_id = TaskId::new();
-        self.sender
-            .send(TaskEvent::AddTask(
-                task_id.clone(),
-                Some(display_name.to_string()),
-                Status::Error,
-            ))
-            .unwrap();
+        let _ = self.try_error(display_name);
     }
 
     pub fn info<S: ToString>(&self, display_name: S) {
-        let task_id = TaskId::new();
-        self.sender
-            .send(TaskEvent::AddTask(
-                task_id.clone(),
-                Some(display_name.to_string()),
-                Status::Info,
-            ))
-            .unwrap();
+        let _ = self.try_info(display_name);
+    }
+
+    pub fn warning<S: ToString>(&self, display_name: S) {
+        let _ = self.try_warning(display_name);
     }
 
     pub fn add_task<S: ToString>(&self, display_name: S, status: Status) -> TaskId {
-        let task_id = TaskId::new_with_sender(self.sender.clone());
-        self.sender
-            .send(TaskEvent::AddTask(
-                task_id.clone(),
-                Some(display_name.to_string()),
-                status,
-            ))
-            .unwrap();
-        return task_id;
+        self.try_add_task(display_name, status)
+            .unwrap_or_else(|_| TaskId::new())
     }
 
     pub fn set_task_display_name(&self, task_id: &TaskId, display_name: String) {
-        self.sender
-            .send(TaskEvent::SetTaskDisplayName(task_id.clone(), display_name))
-            .unwrap();
+        let _ = self.try_set_task_display_name(task_id, display_name);
     }
 
     pub fn delete_task(&self, task_id: &TaskId) {
-        self.sender
-            .send(TaskEvent::DeleteTask(task_id.clone()))
-            .unwrap();
+        let _ = self.try_delete_task(task_id);
     }
 
     pub fn update_task(&self, task_id: &TaskId, new_status: Status) {
-        self.sender
-            .send(TaskEvent::UpdateTask(task_id.clone(), new_status))
-            .unwrap();
+        let _ = self.try_update_task(task_id, new_status);
     }
 
+    // Reports how far along a task is, e.g. bytes downloaded out of the total.
+    // This takes priority over any subtasks when drawing the progress bar.
+    pub fn set_progress(&self, task_id: &TaskId, current: u64, total: u64) {
+        let _ = self.try_set_progress(task_id, current, total);
+    }
+
+    // Bumps a task's current progress by some amount, keeping its total.
+    pub fn inc_progress(&self, task_id: &TaskId, amount: u64) {
+        let _ = self.try_inc_progress(task_id, amount);
+    }
+
+    // Adds a child to any task, including other subtasks, so that
+    // tasks can be nested as deeply as needed.
+    // Just like with tasks, the subtask finishes once its last handle drops.
     pub fn add_subtask(&self, task_id: &TaskId, status: Status) -> TaskId {
-        let sub_task_id = TaskId::new();
-        self.sender
-            .send(TaskEvent::AddSubTask(
-                task_id.clone(),
-                sub_task_id.clone(),
-                None,
-                status,
-            ))
-            .unwrap();
-        return sub_task_id;
+        self.try_add_subtask(task_id, status)
+            .unwrap_or_else(|_| TaskId::new())
+    }
+
+    // Same as `add_subtask`, but with a name to show underneath its parent, e.g. "compiling foo.rs".
+    pub fn add_named_subtask<S: ToString>(
+        &self,
+        task_id: &TaskId,
+        display_name: S,
+        status: Status,
+    ) -> TaskId {
+        self.try_add_named_subtask(task_id, display_name, status)
+            .unwrap_or_else(|_| TaskId::new())
     }
 
     pub fn update_subtask(&self, task_id: &TaskId, sub_task_id: &TaskId, status: Status) {
-        self.sender
-            .send(TaskEvent::UpdateSubTask(
-                task_id.clone(),
-                sub_task_id.clone(),
-                status,
-            ))
-            .unwrap();
+        let _ = self.try_update_subtask(task_id, sub_task_id, status);
+    }
+
+    pub fn try_error<S: ToString>(&self, display_name: S) -> Result<(), Error> {
+        let task_id = TaskId::new();
+        self.send(TaskEvent::AddTask(
+            task_id,
+            Some(display_name.to_string()),
+            Status::Error,
+        ))
+    }
+
+    pub fn try_info<S: ToString>(&self, display_name: S) -> Result<(), Error> {
+        let task_id = TaskId::new();
+        self.send(TaskEvent::AddTask(
+            task_id,
+            Some(display_name.to_string()),
+            Status::Info,
+        ))
+    }
+
+    pub fn try_warning<S: ToString>(&self, display_name: S) -> Result<(), Error> {
+        let task_id = TaskId::new();
+        self.send(TaskEvent::AddTask(
+            task_id,
+            Some(display_name.to_string()),
+            Status::Warning,
+        ))
+    }
+
+    pub fn try_add_task<S: ToString>(
+        &self,
+        display_name: S,
+        status: Status,
+    ) -> Result<TaskId, Error> {
+        let task_id = TaskId::new_with_sender(self.sender.clone(), self.error_on_panic);
+        self.send(TaskEvent::AddTask(
+            task_id.make_weak(),
+            Some(display_name.to_string()),
+            status,
+        ))?;
+        return Ok(task_id);
+    }
+
+    pub fn try_set_task_display_name(
+        &self,
+        task_id: &TaskId,
+        display_name: String,
+    ) -> Result<(), Error> {
+        self.send(TaskEvent::SetTaskDisplayName(
+            task_id.make_weak(),
+            display_name,
+        ))
+    }
+
+    pub fn try_delete_task(&self, task_id: &TaskId) -> Result<(), Error> {
+        self.send(TaskEvent::DeleteTask(task_id.make_weak()))
+    }
+
+    pub fn try_update_task(&self, task_id: &TaskId, new_status: Status) -> Result<(), Error> {
+        self.send(TaskEvent::UpdateTask(task_id.make_weak(), new_status))
+    }
+
+    pub fn try_set_progress(
+        &self,
+        task_id: &TaskId,
+        current: u64,
+        total: u64,
+    ) -> Result<(), Error> {
+        self.send(TaskEvent::SetProgress(task_id.make_weak(), current, total))
+    }
+
+    pub fn try_inc_progress(&self, task_id: &TaskId, amount: u64) -> Result<(), Error> {
+        self.send(TaskEvent::IncProgress(task_id.make_weak(), amount))
+    }
+
+    pub fn try_add_subtask(&self, task_id: &TaskId, status: Status) -> Result<TaskId, Error> {
+        let sub_task_id = TaskId::new_with_sender(self.sender.clone(), self.error_on_panic);
+        self.send(TaskEvent::AddSubTask(
+            task_id.make_weak(),
+            sub_task_id.make_weak(),
+            None,
+            status,
+        ))?;
+        return Ok(sub_task_id);
+    }
+
+    pub fn try_add_named_subtask<S: ToString>(
+        &self,
+        task_id: &TaskId,
+        display_name: S,
+        status: Status,
+    ) -> Result<TaskId, Error> {
+        let sub_task_id = TaskId::new_with_sender(self.sender.clone(), self.error_on_panic);
+        self.send(TaskEvent::AddSubTask(
+            task_id.make_weak(),
+            sub_task_id.make_weak(),
+            Some(display_name.to_string()),
+            status,
+        ))?;
+        return Ok(sub_task_id);
+    }
+
+    pub fn try_update_subtask(
+        &self,
+        task_id: &TaskId,
+        sub_task_id: &TaskId,
+        status: Status,
+    ) -> Result<(), Error> {
+        self.send(TaskEvent::UpdateSubTask(
+            task_id.make_weak(),
+            sub_task_id.make_weak(),
+            status,
+        ))
+    }
+
+    // A recap of everything so far. Once the board has shut down, this is
+    // the recap of the whole run.
+    pub fn summary(&self) -> Summary {
+        self.try_summary().unwrap_or_default()
+    }
+
+    pub fn try_summary(&self) -> Result<Summary, Error> {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        if self.send(TaskEvent::Summarize(sender)).is_ok()
+            && let Ok(summary) = receiver.recv()
+        {
+            return Ok(summary);
+        }
+
+        // Nobody's left to ask, so go with whatever it finished with
+        let final_summary = match self.render_thread.final_summary.lock() {
+            Ok(final_summary) => final_summary.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        };
+        final_summary.ok_or(Error::Disconnected)
+    }
+
+    // Every task so far as a JUnit XML report, for CI to pick up.
+    pub fn junit_report(&self) -> String {
+        self.try_junit_report().unwrap_or_default()
+    }
+
+    pub fn try_junit_report(&self) -> Result<String, Error> {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        self.send(TaskEvent::ExportJUnit(sender))?;
+        receiver.recv().map_err(|_| Error::Disconnected)
+    }
+
+    pub fn write_junit_report<P: AsRef<Path>>(&self, path: P) {
+        let _ = self.try_write_junit_report(path);
+    }
+
+    pub fn try_write_junit_report<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let report = self.try_junit_report()?;
+        std::fs::write(path, report).map_err(|err| Error::Io(err.kind()))
+    }
+
+    pub(crate) fn send(&self, event: TaskEvent) -> Result<(), Error> {
+        self.sender.send(event).map_err(|_| Error::Disconnected)
+    }
+}
+
+impl RenderThread {
+    fn shutdown(&self) -> Result<(), Error> {
+        let maybe_handle = match self.handle.lock() {
+            Ok(mut handle) => handle.take(),
+            Err(poisoned) => poisoned.into_inner().take(),
+        };
+
+        match maybe_handle {
+            Some(handle) => {
+                // If the render thread already died then it can't hear us,
+                // but joining it still tells us that it panicked.
+                let _ = self.sender.send(TaskEvent::Shutdown);
+                let summary = handle.join().map_err(|_| Error::RenderThreadPanicked)?;
+                match self.final_summary.lock() {
+                    Ok(mut final_summary) => *final_summary = Some(summary),
+                    Err(poisoned) => *poisoned.into_inner() = Some(summary),
+                }
+                Ok(())
+            }
+            // Already shut down
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for RenderThread {
+    fn drop(&mut self) {
+        let _ = self.shutdown();
+    }
+}
+
+fn build_error_recap(errors: &[String]) -> String {
+    let mut recap = format!("Errors ({}):", errors.len());
+    for error in errors {
+        recap += &format!("\n    {}", error.bright_red());
+    }
+    recap
+}
+
+fn build_frame(internal_state: &mut InternalState, width: usize, config: &SBStateConfig) -> Frame {
+    let num_finished = internal_state.get_num_finished();
+
+    let num_total = internal_state.get_total();
+    let mut header = format!(
+        "Finished tasks: {} / {}",
+        format!("{}", num_finished).bright_green(),
+        num_total,
+    );
+
+    // Call out anything that didn't actually finish
+    let abandoned = config
+        .statuses
+        .iter()
+        .filter(|definition| definition.is_abandoned)
+        .map(|definition| (internal_state.count(definition.status), definition.status))
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, status)| format!("{} {}", count, status.name().to_lowercase()))
+        .collect::<Vec<_>>();
+    if !abandoned.is_empty() {
+        header += &format!(" ({})", abandoned.join(", "));
+    }
+
+    let task_name_fit = match config.task_name_width {
+        TaskNameWidth::Min(max) => ColumnFit::MIN((max.min(1.0) * width as f32) as usize),
+        TaskNameWidth::Max(max) => ColumnFit::MAX((max.min(1.0) * width as f32) as usize),
+        TaskNameWidth::ExactRatio(max) => ColumnFit::EXACT((max.min(1.0) * width as f32) as usize),
+        TaskNameWidth::ExactChars(max) => ColumnFit::EXACT(max.min(width)),
+    };
+
+    // Tasks with statuses that were never registered still get listed, after everything else
+    let unregistered_sections = internal_state
+        .statuses()
+        .iter()
+        .filter(|definition| config.statuses.get(definition.status).is_none())
+        .map(|definition| SectionConfig::new(definition.status, None))
+        .collect::<Vec<_>>();
+
+    // However cramped things get, nothing should spill past the edge
+    let sections = config
+        .sections
+        .iter()
+        .chain(&unregistered_sections)
+        .filter_map(|section| internal_state.build_section(section, width, task_name_fit, config))
+        .map(|section| FrameSection {
+            title: clamp_width(&section.title, width),
+            rows: section
+                .rows
+                .iter()
+                .map(|row| clamp_width(row, width))
+                .collect(),
+        })
+        .collect();
+
+    Frame {
+        width,
+        header: clamp_width(&header, width),
+        sections,
+        num_finished,
+        num_total,
     }
 }
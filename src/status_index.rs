@@ -0,0 +1,199 @@
+use std::collections::VecDeque;
+
+use rustc_hash::FxHashMap;
+
+use crate::Status;
+
+// Task ids in the order they were added, with constant time (amortized)
+// insertion and removal.
+// Removed ids are only marked as such, and are skipped over until
+// there are enough of them that it's worth compacting.
+#[derive(Default, Debug, Clone)]
+pub struct StatusIndex {
+    entries: VecDeque<(u64, i32)>,
+    live: FxHashMap<i32, u64>,
+    next_sequence: u64,
+}
+
+impl StatusIndex {
+    pub fn len(&self) -> usize {
+        self.live.len()
+    }
+
+    pub fn push(&mut self, id: i32) {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        self.entries.push_back((sequence, id));
+        self.live.insert(id, sequence);
+    }
+
+    pub fn remove(&mut self, id: i32) -> bool {
+        if self.live.remove(&id).is_none() {
+            return false;
+        }
+
+        // Most tasks leave in roughly the order they arrived, so this
+        // usually keeps the front clean without a full compaction.
+        while let Some(&(sequence, id)) = self.entries.front() {
+            if self.is_live(sequence, id) {
+                break;
+            }
+            self.entries.pop_front();
+        }
+
+        if self.entries.len() > 2 * self.live.len() + 32 {
+            let live = &self.live;
+            self.entries
+                .retain(|(sequence, id)| live.get(id) == Some(sequence));
+        }
+
+        return true;
+    }
+
+    // The oldest id still in here.
+    pub fn first(&self) -> Option<i32> {
+        self.iter().next()
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = i32> + '_ {
+        self.entries
+            .iter()
+            .filter(|(sequence, id)| self.is_live(*sequence, *id))
+            .map(|(_, id)| *id)
+    }
+
+    fn is_live(&self, sequence: u64, id: i32) -> bool {
+        self.live.get(&id) == Some(&sequence)
+    }
+}
+
+// One ordered index per status.
+#[derive(Default, Debug, Clone)]
+pub struct StatusIndices {
+    by_status: FxHashMap<Status, StatusIndex>,
+}
+
+impl StatusIndices {
+    pub fn insert(&mut self, status: Status, id: i32) {
+        self.by_status.entry(status).or_default().push(id);
+    }
+
+    pub fn remove(&mut self, status: Status, id: i32) -> bool {
+        match self.by_status.get_mut(&status) {
+            Some(index) => index.remove(id),
+            None => false,
+        }
+    }
+
+    pub fn len(&self, status: Status) -> usize {
+        self.by_status
+            .get(&status)
+            .map(StatusIndex::len)
+            .unwrap_or_default()
+    }
+
+    pub fn first(&self, status: Status) -> Option<i32> {
+        self.by_status.get(&status).and_then(StatusIndex::first)
+    }
+
+    pub fn iter(&self, status: Status) -> impl DoubleEndedIterator<Item = i32> + '_ {
+        self.by_status
+            .get(&status)
+            .into_iter()
+            .flat_map(StatusIndex::iter)
+    }
+
+    // Every id, regardless of status.
+    pub fn all(&self) -> impl Iterator<Item = i32> + '_ {
+        self.by_status.values().flat_map(StatusIndex::iter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(index: &StatusIndex) -> Vec<i32> {
+        index.iter().collect()
+    }
+
+    fn index_of(ids: impl IntoIterator<Item = i32>) -> StatusIndex {
+        let mut index = StatusIndex::default();
+        for id in ids {
+            index.push(id);
+        }
+        index
+    }
+
+    #[test]
+    fn removes_from_the_middle() {
+        let mut index = index_of([1, 2, 3]);
+        assert!(index.remove(2));
+        assert!(!index.remove(2));
+        assert_eq!(ids(&index), [1, 3]);
+        assert_eq!(index.len(), 2);
+
+        // Only marked as removed, since it wasn't at the front
+        assert_eq!(index.entries.len(), 3);
+    }
+
+    #[test]
+    fn removes_from_the_front() {
+        let mut index = index_of([1, 2, 3]);
+        assert!(index.remove(2));
+        assert!(index.remove(1));
+        assert_eq!(ids(&index), [3]);
+        assert_eq!(index.first(), Some(3));
+
+        // Both dead entries get popped once the front is dead
+        assert_eq!(index.entries.len(), 1);
+    }
+
+    #[test]
+    fn reinserts_a_removed_id_at_the_back() {
+        // e.g. a task going from started to paused and back again
+        let mut index = index_of([1, 2, 3]);
+        assert!(index.remove(2));
+        index.push(2);
+        assert_eq!(ids(&index), [1, 3, 2]);
+        assert_eq!(index.len(), 3);
+
+        // The old entry for 2 is stale, so removing it again only removes the new one
+        assert!(index.remove(2));
+        assert!(!index.remove(2));
+        assert_eq!(ids(&index), [1, 3]);
+    }
+
+    #[test]
+    fn keeps_the_order_after_compacting() {
+        let mut index = index_of(0..100);
+
+        // Keep the front alive so that nothing gets popped, forcing a compaction
+        for id in (1..100).filter(|id| id % 10 != 0) {
+            assert!(index.remove(id));
+        }
+        assert!(index.entries.len() <= 2 * index.len() + 32);
+        assert_eq!(ids(&index), (0..100).step_by(10).collect::<Vec<_>>());
+
+        index.push(5);
+        assert_eq!(ids(&index), [0, 10, 20, 30, 40, 50, 60, 70, 80, 90, 5]);
+    }
+
+    #[test]
+    fn skips_dead_entries_from_either_end() {
+        let mut index = index_of([1, 2, 3, 4]);
+        assert!(index.remove(4));
+        assert!(index.remove(2));
+        assert_eq!(index.first(), Some(1));
+        assert_eq!(index.iter().rev().collect::<Vec<_>>(), [3, 1]);
+
+        assert!(index.remove(1));
+        assert_eq!(index.first(), Some(3));
+        assert_eq!(index.iter().next_back(), Some(3));
+
+        assert!(index.remove(3));
+        assert_eq!(index.first(), None);
+        assert_eq!(index.iter().next_back(), None);
+        assert_eq!(index.len(), 0);
+    }
+}
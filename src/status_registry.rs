@@ -0,0 +1,160 @@
+use std::sync::Mutex;
+
+use rustc_hash::FxHashSet;
+
+use crate::Status;
+
+// How the status board should treat tasks with a given status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusDefinition {
+    pub status: Status,
+
+    // Whether tasks with this status are done running, one way or another.
+    pub is_finished: bool,
+
+    // Messages (info, warnings and errors) aren't actual tasks, so they aren't
+    // counted as part of the total, and only stick around for as long as
+    // `info_retention` (or `error_retention`, for failures) allows.
+    pub is_message: bool,
+
+    // Something went wrong. Reported as a failure in JUnit reports and
+    // listed in the error recap.
+    pub is_failure: bool,
+
+    // Done without ever actually running to completion, e.g. cancelled or skipped.
+    // Called out in the header, and reported as skipped in JUnit reports.
+    pub is_abandoned: bool,
+}
+
+impl StatusDefinition {
+    pub(crate) fn counts_towards_total(&self) -> bool {
+        !self.is_message
+    }
+
+    // Counted as finished in the header, and in the progress of any parent.
+    pub(crate) fn counts_as_finished(&self) -> bool {
+        self.is_finished && self.counts_towards_total()
+    }
+}
+
+// Every status the status board knows about, built-in or custom.
+// Unfinished statuses are kept roughly from most to least active, which is
+// the order unfinished subtasks are listed in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusRegistry {
+    definitions: Vec<StatusDefinition>,
+}
+
+impl StatusRegistry {
+    // Adds (or redefines) a custom status, which can then be used like any other.
+    // Unfinished custom statuses are assumed to be more active than just being queued.
+    // Only `SBStateConfig::register_status` should call this, so that every
+    // registered status also gets a section.
+    pub(crate) fn register(&mut self, status: Status, is_finished: bool) {
+        self.definitions
+            .retain(|definition| definition.status != status);
+
+        let definition = StatusDefinition {
+            status,
+            is_finished,
+            is_message: false,
+            is_failure: false,
+            is_abandoned: false,
+        };
+        let position = if is_finished {
+            self.definitions.len()
+        } else {
+            self.definitions
+                .iter()
+                .position(|definition| definition.status == Status::Queued)
+                .unwrap_or(self.definitions.len())
+        };
+        self.definitions.insert(position, definition);
+    }
+
+    pub fn get(&self, status: Status) -> Option<&StatusDefinition> {
+        self.definitions
+            .iter()
+            .find(|definition| definition.status == status)
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &StatusDefinition> + '_ {
+        self.definitions.iter()
+    }
+
+    // Anything that was never registered is treated as an unfinished task.
+    pub(crate) fn is_finished(&self, status: Status) -> bool {
+        self.get(status)
+            .map(|definition| definition.is_finished)
+            .unwrap_or_default()
+    }
+
+    // Anything unfinished that isn't just waiting in the queue, custom statuses included.
+    // Only running tasks are timed.
+    pub(crate) fn is_running(&self, status: Status) -> bool {
+        status != Status::Queued && !self.is_finished(status)
+    }
+
+    // Unfinished statuses, from most to least active.
+    pub(crate) fn active(&self) -> impl Iterator<Item = Status> + '_ {
+        self.definitions
+            .iter()
+            .filter(|definition| !definition.is_finished)
+            .map(|definition| definition.status)
+    }
+}
+
+impl Default for StatusRegistry {
+    fn default() -> Self {
+        let task = |status, is_finished, is_abandoned| StatusDefinition {
+            status,
+            is_finished,
+            is_message: false,
+            is_failure: false,
+            is_abandoned,
+        };
+        let message = |status, is_failure| StatusDefinition {
+            status,
+            is_finished: true,
+            is_message: true,
+            is_failure,
+            is_abandoned: false,
+        };
+
+        Self {
+            definitions: vec![
+                task(Status::Started, false, false),
+                task(Status::Retrying, false, false),
+                task(Status::Blocked, false, false),
+                task(Status::Paused, false, false),
+                task(Status::Queued, false, false),
+                task(Status::Finished, true, false),
+                task(Status::Cancelled, true, true),
+                task(Status::Skipped, true, true),
+                message(Status::Error, true),
+                message(Status::Info, false),
+                message(Status::Warning, false),
+            ],
+        }
+    }
+}
+
+// Custom status names have to live forever (so that statuses stay `Copy`),
+// so each distinct one is only ever leaked once.
+pub(crate) fn intern_name(name: &str) -> &'static str {
+    static NAMES: Mutex<Option<FxHashSet<&'static str>>> = Mutex::new(None);
+
+    let mut names = match NAMES.lock() {
+        Ok(names) => names,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    let names = names.get_or_insert_with(FxHashSet::default);
+    match names.get(name) {
+        Some(existing) => existing,
+        None => {
+            let leaked: &'static str = Box::leak(name.to_string().into_boxed_str());
+            names.insert(leaked);
+            leaked
+        }
+    }
+}
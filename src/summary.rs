@@ -0,0 +1,84 @@
+use std::{fmt::Display, time::Duration};
+
+use crate::{Status, internal_state::format_duration};
+
+// A recap of everything that happened on the status board.
+// Only covers the top-level tasks, and the per-task details and subtask counts
+// only include tasks that are still around (see `finished_retention`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Summary {
+    // How long the status board has been (or was) running for.
+    pub wall_time: Duration,
+
+    // How many tasks ended up with each status, leaving out any that nothing had.
+    pub status_counts: Vec<(Status, usize)>,
+
+    // The tasks that took the longest to run, slowest first.
+    pub slowest_tasks: Vec<TaskSummary>,
+
+    // Every task (whether or not it ever ran), in the order they were added.
+    pub tasks: Vec<TaskSummary>,
+
+    // Every error, oldest first, including the ones that are no longer shown.
+    pub errors: Vec<String>,
+
+    // How many of the subtasks of each task with any have finished,
+    // as (name, finished, total). Subtasks are counted by their leaves.
+    pub subtask_completion: Vec<(String, usize, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskSummary {
+    pub name: String,
+    pub status: Status,
+
+    // How long it spent queued before it started.
+    pub queue_wait: Duration,
+
+    // How long it ran for, from when it first started until it first finished.
+    // Zero if it never started.
+    pub run_time: Duration,
+
+    // Every status it's had, oldest first, along with how long after
+    // the status board started it got each one.
+    pub history: Vec<(Duration, Status)>,
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Summary (took {}):", format_duration(self.wall_time))?;
+        for (status, count) in &self.status_counts {
+            write!(f, "\n    {}: {}", status.name(), count)?;
+        }
+
+        if !self.slowest_tasks.is_empty() {
+            write!(f, "\nSlowest tasks:")?;
+            for task in &self.slowest_tasks {
+                write!(
+                    f,
+                    "\n    {} ({}, queued for {}): {}",
+                    task.name,
+                    task.status.name(),
+                    format_duration(task.queue_wait),
+                    format_duration(task.run_time)
+                )?;
+            }
+        }
+
+        if !self.errors.is_empty() {
+            write!(f, "\nErrors ({}):", self.errors.len())?;
+            for error in &self.errors {
+                write!(f, "\n    {}", error)?;
+            }
+        }
+
+        if !self.subtask_completion.is_empty() {
+            write!(f, "\nSubtasks:")?;
+            for (name, finished, total) in &self.subtask_completion {
+                write!(f, "\n    {}: {} / {}", name, finished, total)?;
+            }
+        }
+
+        Ok(())
+    }
+}
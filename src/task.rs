@@ -1,22 +1,119 @@
-use crate::{Status, TaskId, internal_state::InternalState};
+use std::time::{Duration, Instant};
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+use crate::{Status, StatusRegistry, TaskId, status_index::StatusIndices};
+
+#[derive(Debug)]
 pub struct Task {
     pub key: TaskId,
     pub display_name: Option<String>,
-    pub time: std::time::Instant,
-    pub substate: InternalState,
+    pub status: Status,
+
+    // Every status this task has had and when it got it, oldest first.
+    // Never empty, the last entry is always the current status.
+    pub history: Vec<(Instant, Status)>,
+
+    // The id of the task this is a subtask of, if any
+    pub parent: Option<i32>,
+    pub children: StatusIndices,
+
+    // Just the children that were given a name, which are all that's listed by default
+    pub named_children: StatusIndices,
+
+    // Rolled up from every descendant, and kept up to date as any of them change
+    pub leaf_counts: LeafCounts,
+
+    // Explicitly reported (current, total) progress, independent of any subtasks
+    pub progress: Option<(u64, u64)>,
+}
+
+// How many of the tasks without any subtasks of their own in some tree have
+// finished or failed, out of how many there are.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LeafCounts {
+    pub finished: usize,
+    pub failed: usize,
+    pub total: usize,
+}
+
+impl LeafCounts {
+    // Swaps what part of the tree used to count for with what it counts for now.
+    pub fn replace(&mut self, old: LeafCounts, new: LeafCounts) {
+        self.finished = self.finished + new.finished - old.finished;
+        self.failed = self.failed + new.failed - old.failed;
+        self.total = self.total + new.total - old.total;
+    }
 }
 
 impl Task {
-    pub fn num_substate_total(&self) -> usize {
-        self.substate.get_total()
+    pub fn new(
+        key: TaskId,
+        display_name: Option<String>,
+        status: Status,
+        parent: Option<i32>,
+    ) -> Self {
+        Self {
+            key,
+            display_name,
+            status,
+            history: vec![(Instant::now(), status)],
+            parent,
+            children: StatusIndices::default(),
+            named_children: StatusIndices::default(),
+            leaf_counts: LeafCounts::default(),
+            progress: None,
+        }
     }
 
-    pub fn num_substate_finished(&self) -> usize {
-        match self.substate.task_map.get(&Status::Finished) {
-            Some(v) => v.len(),
-            None => 0,
+    pub fn name(&self) -> String {
+        self.display_name
+            .clone()
+            .unwrap_or_else(|| self.key.to_string())
+    }
+
+    pub fn set_status(&mut self, status: Status) {
+        self.status = status;
+        self.history.push((Instant::now(), status));
+    }
+
+    // When this task got its current status
+    pub fn status_time(&self) -> Instant {
+        self.history[self.history.len() - 1].0
+    }
+
+    // Whether this task spent any time queued at all.
+    pub fn was_queued(&self) -> bool {
+        self.history[0].1 == Status::Queued
+    }
+
+    // When this task first stopped being queued, if it has yet.
+    fn start_time(&self) -> Option<Instant> {
+        self.history
+            .iter()
+            .find(|(_, status)| *status != Status::Queued)
+            .map(|(time, _)| *time)
+    }
+
+    // How long this task was (or so far has been) queued for.
+    pub fn queue_wait(&self) -> Duration {
+        let added = self.history[0].0;
+        match self.start_time() {
+            Some(start) => start.duration_since(added),
+            None => added.elapsed(),
         }
     }
+
+    // How long this task has been running for since it was first taken off the queue,
+    // up until it first finished. Retries or pauses along the way are included.
+    pub fn run_time(&self, statuses: &StatusRegistry) -> Option<Duration> {
+        let start = self.start_time()?;
+        let end = self
+            .history
+            .iter()
+            .find(|(_, status)| statuses.is_finished(*status))
+            .map(|(time, _)| *time);
+        return Some(match end {
+            Some(end) => end.duration_since(start),
+            None => start.elapsed(),
+        });
+    }
 }
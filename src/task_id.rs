@@ -15,6 +15,9 @@ static LATEST_ID: AtomicI32 = AtomicI32::new(0);
 pub struct TaskId {
     pub id: i32,
     maybe_sender: Option<Arc<Sender<TaskEvent>>>,
+
+    // If this drops while panicking, should we mark the task as an error?
+    error_on_panic: bool,
 }
 
 impl TaskId {
@@ -23,14 +26,16 @@ impl TaskId {
         Self {
             id,
             maybe_sender: None,
+            error_on_panic: false,
         }
     }
 
-    pub(crate) fn new_with_sender(sender: Sender<TaskEvent>) -> Self {
+    pub(crate) fn new_with_sender(sender: Sender<TaskEvent>, error_on_panic: bool) -> Self {
         let id = LATEST_ID.fetch_add(1, Ordering::SeqCst) + 1;
         Self {
             id,
             maybe_sender: Some(Arc::new(sender)),
+            error_on_panic,
         }
     }
 
@@ -38,10 +43,17 @@ impl TaskId {
         Self {
             id: self.id,
             maybe_sender: None,
+            error_on_panic: self.error_on_panic,
         }
     }
 }
 
+impl Default for TaskId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Eq for TaskId {}
 impl PartialEq for TaskId {
     fn eq(&self, other: &Self) -> bool {
@@ -49,6 +61,25 @@ impl PartialEq for TaskId {
     }
 }
 
+// Only the id is kept, so anything deserialized is weak.
+#[cfg(feature = "serde")]
+impl serde::Serialize for TaskId {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i32(self.id)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for TaskId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self {
+            id: i32::deserialize(deserializer)?,
+            maybe_sender: None,
+            error_on_panic: false,
+        })
+    }
+}
+
 impl Display for TaskId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.id)
@@ -58,12 +89,19 @@ impl Display for TaskId {
 impl Drop for TaskId {
     fn drop(&mut self) {
         if let Some(sender_rc) = self.maybe_sender.take() {
-            // This is about to drop, so let's go ahead and mark this task as "finished".
+            // This is about to drop, so let's go ahead and mark this task as "finished",
+            // unless it already finished some other way (e.g. it was cancelled).
             if let Some(sender) = Arc::into_inner(sender_rc) {
-                sender
-                    // Don't pass the sender in order to avoid infinite loops
-                    .send(TaskEvent::UpdateTask(self.make_weak(), Status::Finished))
-                    .unwrap();
+                // If whoever owned this task panicked then it probably didn't actually finish
+                let status = if self.error_on_panic && std::thread::panicking() {
+                    Status::Error
+                } else {
+                    Status::Finished
+                };
+
+                // Don't pass the sender in order to avoid infinite loops.
+                // If the board has already shut down then there's nobody left to tell.
+                let _ = sender.send(TaskEvent::FinishOnDrop(self.make_weak(), status));
             }
         }
     }
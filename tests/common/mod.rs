@@ -0,0 +1,47 @@
+// Setup shared by the integration tests: render a board into memory and pick
+// the final frame apart by section.
+#![allow(dead_code)]
+
+use cli_status_board::{Frame, MemoryRenderer, SBState, SBStateConfig};
+
+// Sets up a board at the given width, lets `build` add whatever it needs to,
+// then shuts down and returns the final frame.
+// Whatever `build` returns (e.g. task handles) is only dropped after shutting down,
+// so that tasks that should still be running are drawn as such.
+pub fn render<T, F: FnOnce(&SBState) -> T>(width: usize, config: SBStateConfig, build: F) -> Frame {
+    let renderer = MemoryRenderer::new(width);
+    let state = SBState::with_renderer(config, renderer.clone());
+    let handles = build(&state);
+    state.shutdown();
+    drop(handles);
+    renderer.last_frame().unwrap()
+}
+
+// Every section of the frame as its uncolored (title, rows).
+pub fn sections(frame: &Frame) -> Vec<(String, Vec<String>)> {
+    let lines = frame.plain_lines();
+
+    // Skip the header, then each section is a blank line and its title before the rows
+    let mut index = 1;
+    let mut sections = Vec::new();
+    for section in &frame.sections {
+        let title = lines[index + 1].clone();
+        index += 2;
+        sections.push((title, lines[index..index + section.rows.len()].to_vec()));
+        index += section.rows.len();
+    }
+    sections
+}
+
+// The uncolored rows of the section titled e.g. "Started", not counting the title itself.
+pub fn section_rows(frame: &Frame, title: &str) -> Vec<String> {
+    sections(frame)
+        .into_iter()
+        .find(|(section_title, _)| {
+            section_title
+                .strip_prefix(title)
+                .is_some_and(|rest| rest.starts_with(" ("))
+        })
+        .map(|(_, rows)| rows)
+        .unwrap_or_else(|| panic!("no {:?} section in {:#?}", title, frame.plain_lines()))
+}
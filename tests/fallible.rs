@@ -0,0 +1,110 @@
+use cli_status_board::{
+    Error, Frame, MemoryRenderer, Renderer, SBState, SBStateConfig, Status, TaskId,
+};
+
+// Crashes the render thread on the first frame it draws.
+struct PanickingRenderer;
+
+impl Renderer for PanickingRenderer {
+    fn size(&self) -> Option<(usize, usize)> {
+        Some((40, 20))
+    }
+
+    fn render(&mut self, _frame: &Frame) {
+        panic!("can't draw");
+    }
+}
+
+// Every update should fail to reach a board that's no longer running,
+// while the plain variants carry on as if nothing happened.
+fn check_disconnected(state: &SBState, task: &TaskId, subtask: &TaskId) {
+    assert_eq!(state.try_error("oops"), Err(Error::Disconnected));
+    assert_eq!(state.try_info("note"), Err(Error::Disconnected));
+    assert_eq!(state.try_warning("careful"), Err(Error::Disconnected));
+    assert_eq!(
+        state.try_add_task("late", Status::Queued).err(),
+        Some(Error::Disconnected)
+    );
+    assert_eq!(
+        state.try_set_task_display_name(task, "renamed".to_string()),
+        Err(Error::Disconnected)
+    );
+    assert_eq!(
+        state.try_update_task(task, Status::Finished),
+        Err(Error::Disconnected)
+    );
+    assert_eq!(state.try_set_progress(task, 1, 2), Err(Error::Disconnected));
+    assert_eq!(state.try_inc_progress(task, 1), Err(Error::Disconnected));
+    assert_eq!(
+        state.try_add_subtask(task, Status::Started).err(),
+        Some(Error::Disconnected)
+    );
+    assert_eq!(
+        state
+            .try_add_named_subtask(task, "late", Status::Started)
+            .err(),
+        Some(Error::Disconnected)
+    );
+    assert_eq!(
+        state.try_update_subtask(task, subtask, Status::Finished),
+        Err(Error::Disconnected)
+    );
+    assert_eq!(state.try_delete_task(task), Err(Error::Disconnected));
+    assert_eq!(state.try_junit_report(), Err(Error::Disconnected));
+    assert_eq!(
+        state.try_write_junit_report(std::env::temp_dir().join("never-written.xml")),
+        Err(Error::Disconnected)
+    );
+
+    state.error("oops");
+    state.info("note");
+    state.warning("careful");
+    let late = state.add_task("late", Status::Queued);
+    state.set_task_display_name(&late, "renamed".to_string());
+    state.update_task(&late, Status::Started);
+    state.set_progress(&late, 1, 2);
+    state.inc_progress(&late, 1);
+    let late_subtask = state.add_subtask(&late, Status::Started);
+    let late_named_subtask = state.add_named_subtask(&late, "late", Status::Started);
+    state.update_subtask(&late, &late_subtask, Status::Finished);
+    state.delete_task(task);
+    assert_eq!(state.junit_report(), "");
+    state.shutdown();
+    drop((late, late_subtask, late_named_subtask));
+}
+
+#[test]
+fn does_nothing_once_shut_down() {
+    let renderer = MemoryRenderer::new(40);
+    let state = SBState::with_renderer(SBStateConfig::default(), renderer.clone());
+    let task = state.add_task("build", Status::Started);
+    let subtask = state.add_subtask(&task, Status::Started);
+    assert_eq!(state.try_shutdown(), Ok(()));
+
+    check_disconnected(&state, &task, &subtask);
+    assert_eq!(state.try_shutdown(), Ok(()));
+
+    // The handles finishing can't reach the board either
+    drop((task, subtask));
+    let frame = renderer.last_frame().unwrap();
+    assert_eq!(frame.plain_lines()[0], "Finished tasks: 0 / 1");
+
+    // The summary still covers the run up until shutting down
+    let summary = state.try_summary().unwrap();
+    assert_eq!(summary.status_counts, [(Status::Started, 1)]);
+}
+
+#[test]
+fn does_nothing_once_the_render_thread_crashes() {
+    let state = SBState::with_renderer(SBStateConfig::default(), PanickingRenderer);
+    let task = state.add_task("build", Status::Started);
+    let subtask = state.add_subtask(&task, Status::Started);
+    assert_eq!(state.try_shutdown(), Err(Error::RenderThreadPanicked));
+
+    check_disconnected(&state, &task, &subtask);
+    drop((task, subtask));
+
+    // Nothing survived the crash to summarize
+    assert_eq!(state.try_summary(), Err(Error::Disconnected));
+    assert_eq!(state.summary(), Default::default());
+}
@@ -0,0 +1,65 @@
+use cli_status_board::{MemoryRenderer, SBState, SBStateConfig, Status};
+
+// Timings differ from run to run, so blank them out.
+fn without_times(report: &str) -> String {
+    let mut result = String::new();
+    let mut rest = report;
+    while let Some(start) = rest.find("time=\"") {
+        let (before, after) = rest.split_at(start + "time=\"".len());
+        result += before;
+        rest = &after[after.find('"').unwrap()..];
+    }
+    result + rest
+}
+
+#[test]
+fn reports_every_task_with_its_outcome() {
+    let state = SBState::with_renderer(SBStateConfig::default(), MemoryRenderer::new(40));
+
+    let build = state.add_task("build <release>", Status::Started);
+    let compile = state.add_named_subtask(&build, "compile \"core\"", Status::Started);
+    state.update_subtask(&build, &compile, Status::Finished);
+    let link = state.add_named_subtask(&build, "link & strip", Status::Started);
+    state.update_subtask(&build, &link, Status::Error);
+    let package = state.add_named_subtask(&build, "package", Status::Queued);
+    let docs = state.add_named_subtask(&package, "docs", Status::Cancelled);
+
+    let lint = state.add_task("lint\u{1}", Status::Skipped);
+    state.error("tests failed: 1 < 2");
+    state.info("not a test");
+
+    // Whatever already finished (one way or another) stays that way as its handle drops
+    drop((compile, link, docs, lint));
+    let report = state.junit_report();
+    state.shutdown();
+    drop((build, package));
+
+    assert_eq!(
+        without_times(&report),
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites tests="7" failures="2" errors="0" skipped="4" time="">
+  <testsuite name="cli-status-board" tests="7" failures="2" errors="0" skipped="4" time="">
+    <testcase name="build &lt;release&gt;" classname="build &lt;release&gt;" time="">
+      <skipped message="Unfinished (Started)"/>
+    </testcase>
+    <testcase name="package" classname="build &lt;release&gt;" time="">
+      <skipped message="Unfinished (Queued)"/>
+    </testcase>
+    <testcase name="docs" classname="build &lt;release&gt;/package" time="">
+      <skipped message="Cancelled"/>
+    </testcase>
+    <testcase name="compile &quot;core&quot;" classname="build &lt;release&gt;" time=""/>
+    <testcase name="link &amp; strip" classname="build &lt;release&gt;" time="">
+      <failure message="link &amp; strip"/>
+    </testcase>
+    <testcase name="lint" classname="lint" time="">
+      <skipped message="Skipped"/>
+    </testcase>
+    <testcase name="tests failed: 1 &lt; 2" classname="tests failed: 1 &lt; 2" time="">
+      <failure message="tests failed: 1 &lt; 2"/>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+    );
+}
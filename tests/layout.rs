@@ -0,0 +1,226 @@
+// Property-style checks that the layout holds up at any width: for lots of
+// combinations of widths, name widths and names, the render thread shouldn't
+// panic, no line should be wider than the frame, and whatever doesn't fit should
+// be dropped whole rather than leaving half a count or a name that's all ellipsis.
+mod common;
+
+use std::time::Duration;
+
+use cli_status_board::{SBStateConfig, Status, TaskNameTruncation, TaskNameWidth};
+use unicode_width::UnicodeWidthStr;
+
+// A tiny deterministic generator (xorshift), so that failures are reproducible.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+// A name of `len` graphemes, mixing narrow, wide, combining and separator characters.
+// Names never start or end with a space, so that what's left of them after truncation
+// can't be mistaken for padding. None of these can be mistaken for other columns either.
+fn random_name(rng: &mut Rng, len: usize) -> String {
+    const PIECES: [&str; 8] = ["a", "Z", "/", "日", "e\u{301}", "🚀", "-", " "];
+    (0..len)
+        .map(|index| match index == 0 || index + 1 == len {
+            true => PIECES[rng.below(PIECES.len() - 1)],
+            false => PIECES[rng.below(PIECES.len())],
+        })
+        .collect()
+}
+
+// Counts are all or nothing, e.g. "3 / 12" but never "3 /" or "3 / 1".
+// Names are never made of digits, so a digit before " /" can only be a count.
+fn assert_whole_counts(row: &str, totals: &[usize], description: &str) {
+    let mut rest = row;
+    while let Some(position) = rest.find(" /") {
+        let (before, after) = (&rest[..position], &rest[position + 2..]);
+        if before.ends_with(|c: char| c.is_ascii_digit()) {
+            // Totals are right aligned, so there may be more than one space
+            let total = after
+                .trim_start()
+                .split([' ', ','])
+                .next()
+                .unwrap_or_default();
+            assert!(
+                totals.iter().any(|t| t.to_string() == total),
+                "{:?} has a partial count at {}",
+                row,
+                description
+            );
+        }
+        rest = after;
+    }
+}
+
+// While any other column is drawn, the name should show more than just the ellipsis.
+fn assert_name_shown(row: &str, ellipsis: &str, description: &str) {
+    let others = ["queued", "ETA", "["]
+        .into_iter()
+        .filter_map(|other| row.find(other))
+        .chain(row.find(|c: char| c.is_ascii_digit()))
+        .min();
+    if let Some(others) = others
+        && !ellipsis.is_empty()
+    {
+        assert_ne!(
+            row[..others].trim(),
+            ellipsis,
+            "{:?} only shows the ellipsis at {}",
+            row,
+            description
+        );
+    }
+}
+
+fn name_widths(width: usize) -> Vec<TaskNameWidth> {
+    vec![
+        TaskNameWidth::Min(0.0),
+        TaskNameWidth::Min(0.5),
+        TaskNameWidth::Max(0.0),
+        TaskNameWidth::Max(0.5),
+        TaskNameWidth::Max(1.0),
+        TaskNameWidth::ExactRatio(0.3),
+        TaskNameWidth::ExactRatio(1.0),
+        TaskNameWidth::ExactChars(0),
+        TaskNameWidth::ExactChars(1),
+        TaskNameWidth::ExactChars(2),
+        TaskNameWidth::ExactChars(width + 10),
+    ]
+}
+
+// Renders a board with a task for each name (every other one with progress, some
+// with subtasks) plus a message, and checks the final frame.
+fn check_layout(width: usize, config: SBStateConfig, names: &[String]) {
+    let description = format!("width {} with {:?}", width, config);
+    let ellipsis = config.ellipsis.clone();
+    let frame = common::render(width, config, |state| {
+        let mut tasks = Vec::new();
+        for (index, name) in names.iter().enumerate() {
+            let task = state.add_task(name, Status::Started);
+            match index % 3 {
+                0 => state.set_progress(&task, index as u64, names.len() as u64),
+                1 => tasks.push(state.add_named_subtask(&task, name, Status::Started)),
+                _ => {}
+            }
+            tasks.push(task);
+        }
+        if let Some(name) = names.last() {
+            state.error(name);
+        }
+        tasks
+    });
+
+    for line in frame.plain_lines() {
+        assert!(
+            line.width() <= width,
+            "{:?} is wider than {}",
+            line,
+            description
+        );
+    }
+
+    // Progress is "index / number of names", and every subtask is its parent's only one
+    let totals = [names.len(), 1];
+    for (_, rows) in common::sections(&frame) {
+        for row in rows {
+            assert_whole_counts(&row, &totals, &description);
+            assert_name_shown(&row, &ellipsis, &description);
+        }
+    }
+}
+
+fn quick_config() -> SBStateConfig {
+    let mut config = SBStateConfig {
+        refresh_rate: Duration::ZERO,
+        subtask_depth: 1,
+        ..Default::default()
+    };
+    for section in config.sections.iter_mut() {
+        section.max_rows = usize::MAX;
+    }
+    config
+}
+
+#[test]
+fn fits_any_width_and_name_width() {
+    let mut rng = Rng(0x5eed);
+    for width in 0..=40 {
+        for task_name_width in name_widths(width) {
+            let names = (0..8)
+                .map(|_| {
+                    let len = rng.below(60);
+                    random_name(&mut rng, len)
+                })
+                .collect::<Vec<_>>();
+            let config = SBStateConfig {
+                task_name_width,
+                ..quick_config()
+            };
+            check_layout(width, config, &names);
+        }
+    }
+}
+
+#[test]
+fn fits_any_width_with_every_option() {
+    let mut rng = Rng(0xc0ffee);
+    for width in [0, 1, 2, 3, 4, 5, 6, 8, 13, 21, 34, 55, 89] {
+        for truncation in [
+            TaskNameTruncation::End,
+            TaskNameTruncation::Start,
+            TaskNameTruncation::Middle,
+            TaskNameTruncation::Path,
+        ] {
+            for (wrap, grow_if_no_progress) in [(false, true), (false, false), (true, true)] {
+                let names = (0..=12)
+                    .map(|len| random_name(&mut rng, len * 5))
+                    .collect::<Vec<_>>();
+                let ellipsis = ["...", "…", "", "<snip>"][rng.below(4)];
+                let mut config = SBStateConfig {
+                    task_name_truncation: truncation,
+                    ellipsis: ellipsis.to_string(),
+                    grow_if_no_progress,
+                    show_elapsed: true,
+                    show_eta: true,
+                    show_rate: true,
+                    show_queue_wait: true,
+                    ..quick_config()
+                };
+                for section in config.sections.iter_mut() {
+                    section.wrap = wrap;
+                }
+                check_layout(width, config, &names);
+            }
+        }
+    }
+}
+
+#[test]
+fn drops_whole_columns_when_narrow() {
+    let rows = |width| {
+        let frame = common::render(width, quick_config(), |state| {
+            let task = state.add_task("download", Status::Started);
+            state.set_progress(&task, 12, 40);
+            state.info("fetching");
+            task
+        });
+        // Titles get cut too, so go by position: the info message comes first
+        let (_, rows) = common::sections(&frame).pop().unwrap();
+        rows
+    };
+    assert_eq!(rows(40), ["    download    12 / 40 [===>.........]"]);
+    assert_eq!(rows(24), ["    down...    12 / 40 "]);
+    assert_eq!(rows(16), ["    download "]);
+    assert_eq!(rows(12), ["    down... "]);
+    assert_eq!(rows(6), ["dow..."]);
+}
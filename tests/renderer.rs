@@ -0,0 +1,111 @@
+mod common;
+
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use cli_status_board::{LogRenderer, MemoryRenderer, SBState, SBStateConfig, Status};
+
+#[test]
+fn renders_sections_at_a_fixed_width() {
+    let renderer = MemoryRenderer::new(40);
+    let state = SBState::with_renderer(SBStateConfig::default(), renderer.clone());
+
+    let started = state.add_task("started task", Status::Started);
+    let queued = state.add_task("queued task", Status::Queued);
+    let finished = state.add_task("finished task", Status::Started);
+    state.update_task(&finished, Status::Finished);
+    state.shutdown();
+
+    let frame = renderer.last_frame().unwrap();
+    assert_eq!(frame.width, 40);
+    assert_eq!(
+        frame.plain_lines(),
+        vec![
+            "Finished tasks: 1 / 3",
+            "",
+            "Started (1):",
+            "    started task                        ",
+            "",
+            "Queued (1):",
+            "    queued task                         ",
+        ]
+    );
+
+    drop((started, queued));
+}
+
+#[test]
+fn truncates_long_names() {
+    let frame = common::render(20, SBStateConfig::default(), |state| {
+        state.add_task("a task with a very long name", Status::Started)
+    });
+    assert_eq!(frame.sections[0].title, "Started (1):");
+    assert_eq!(
+        common::section_rows(&frame, "Started"),
+        ["    a task with ... "]
+    );
+}
+
+// Collects everything written to it, while still letting the test read it back.
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuffer {
+    // Every line so far, without the timestamps.
+    fn lines(&self) -> Vec<String> {
+        let buffer = self.0.lock().unwrap();
+        String::from_utf8_lossy(&buffer)
+            .lines()
+            .map(|line| match line.split_once("s] ") {
+                Some((_, rest)) => rest.to_string(),
+                None => line.to_string(),
+            })
+            .collect()
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn logs_transitions_and_summarizes_while_idle() {
+    let buffer = SharedBuffer::default();
+    let renderer = LogRenderer::new(buffer.clone(), Duration::from_millis(200));
+    let state = SBState::with_renderer(SBStateConfig::default(), renderer);
+
+    let build = state.add_task("build", Status::Queued);
+    state.update_task(&build, Status::Started);
+    state.update_task(&build, Status::Finished);
+    let test = state.add_task("test", Status::Started);
+
+    // Nothing else happens, but the summary should still show up in the meantime
+    std::thread::sleep(Duration::from_millis(700));
+    assert_eq!(
+        buffer.lines(),
+        [
+            "Queued: build",
+            "Started: build",
+            "Finished: build",
+            "Started: test",
+            "Finished tasks: 1 / 2",
+        ]
+    );
+
+    // Only written again once the counts change
+    drop(test);
+    state.shutdown();
+    assert_eq!(
+        buffer.lines()[5..],
+        ["Finished: test", "Finished tasks: 2 / 2"]
+    );
+}
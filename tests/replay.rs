@@ -0,0 +1,77 @@
+#![cfg(feature = "serde")]
+
+mod common;
+
+use std::{
+    fs::File,
+    io::{BufReader, Cursor},
+    path::PathBuf,
+};
+
+use cli_status_board::{Error, ReplaySpeed, SBStateConfig, Status};
+
+// Records a short run to a fresh event log and returns where it is.
+fn record_run(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("{}-{}.jsonl", name, std::process::id()));
+    let config = SBStateConfig {
+        event_log_path: Some(path.clone()),
+        ..Default::default()
+    };
+    common::render(40, config, |state| {
+        let recorded = state.add_task("rec", Status::Started);
+        state.set_progress(&recorded, 3, 10);
+        drop(state.add_task("done", Status::Started));
+        (recorded, state.add_task("still running", Status::Started))
+    });
+    path
+}
+
+#[test]
+fn replays_a_recorded_run() {
+    let path = record_run("replays-a-recorded-run");
+    let frame = common::render(40, SBStateConfig::default(), |state| {
+        let live = state.add_task("live", Status::Started);
+
+        // Twice, so that the second replay would clash with the first one's ids
+        for _ in 0..2 {
+            let log = BufReader::new(File::open(&path).unwrap());
+            assert_eq!(state.replay(log, ReplaySpeed::Instant), Ok(()));
+        }
+
+        // Recorded somewhere else, with an id that happens to match a live task's
+        let log = format!(
+            "{{\"time\":0.0,\"event\":{{\"UpdateTask\":[{},\"Finished\"]}}}}\n",
+            live.id
+        );
+        assert_eq!(state.replay(Cursor::new(log), ReplaySpeed::Instant), Ok(()));
+        live
+    });
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(frame.plain_lines()[0], "Finished tasks: 2 / 7");
+    assert_eq!(
+        common::section_rows(&frame, "Started"),
+        [
+            "    live                                ",
+            "    rec              3 / 10 [==>......]",
+            "    still running                       ",
+            "    rec              3 / 10 [==>......]",
+            "    still running                       ",
+        ]
+    );
+}
+
+#[test]
+fn rejects_speeds_that_arent_positive() {
+    let path = record_run("rejects-speeds-that-arent-positive");
+    common::render(40, SBStateConfig::default(), |state| {
+        for factor in [0.0, -1.0, f64::NAN] {
+            let log = BufReader::new(File::open(&path).unwrap());
+            assert_eq!(
+                state.replay(log, ReplaySpeed::Scaled(factor)),
+                Err(Error::InvalidReplaySpeed)
+            );
+        }
+    });
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use std::time::Duration;
+
+use cli_status_board::{MemoryRenderer, Retention, SBState, SBStateConfig, Status};
+
+// Finished tasks are normally hidden, so show them to see which were kept.
+fn config(finished_retention: Retention) -> SBStateConfig {
+    let mut config = SBStateConfig {
+        finished_retention,
+        ..Default::default()
+    };
+    for section in config.sections.iter_mut() {
+        section.visible = true;
+    }
+    config
+}
+
+fn finish_tasks(state: &SBState, count: usize) {
+    for index in 0..count {
+        let task = state.add_task(format!("task {}", index), Status::Started);
+        state.update_task(&task, Status::Finished);
+    }
+}
+
+#[test]
+fn keeps_counting_evicted_tasks() {
+    let frame = common::render(30, config(Retention::keep_last(2)), |state| {
+        finish_tasks(state, 5);
+        state.add_task("still running", Status::Started)
+    });
+
+    assert_eq!(frame.plain_lines()[0], "Finished tasks: 5 / 6");
+    assert_eq!(
+        common::section_rows(&frame, "Finished"),
+        [
+            "    task 4                    ",
+            "    task 3                    ",
+        ]
+    );
+}
+
+#[test]
+fn evicts_tasks_that_finished_too_long_ago() {
+    let frame = common::render(30, config(Retention::keep_for(Duration::ZERO)), |state| {
+        finish_tasks(state, 3);
+    });
+
+    assert_eq!(frame.plain_lines()[0], "Finished tasks: 3 / 3");
+    assert!(
+        common::sections(&frame)
+            .iter()
+            .all(|(title, _)| !title.starts_with("Finished")),
+        "{:#?}",
+        frame.plain_lines()
+    );
+}
+
+#[test]
+fn recaps_errors_that_expired() {
+    let renderer = MemoryRenderer::new(40);
+    let config = SBStateConfig {
+        error_retention: Retention::keep_for(Duration::ZERO),
+        error_recap: true,
+        ..Default::default()
+    };
+    let state = SBState::with_renderer(config, renderer.clone());
+    state.error("disk full");
+    state.error("out of memory");
+    state.shutdown();
+
+    let frame = renderer.last_frame().unwrap();
+    assert_eq!(frame.plain_lines(), ["Finished tasks: 0 / 0"]);
+    assert_eq!(
+        renderer.reports(),
+        ["Errors (2):\n    disk full\n    out of memory"]
+    );
+}
+
+#[test]
+fn keeps_each_status_for_as_long_as_its_section_says() {
+    let mut config = SBStateConfig {
+        info_retention: Retention::keep_for(Duration::ZERO),
+        ..Default::default()
+    };
+    for section in config.sections.iter_mut() {
+        if section.status == Status::Warning {
+            section.retention = Some(Retention::keep_last(1));
+        }
+    }
+
+    let frame = common::render(30, config, |state| {
+        state.info("fetching");
+        state.warning("slow mirror");
+        state.warning("retrying download");
+    });
+    let titles = common::sections(&frame)
+        .into_iter()
+        .map(|(title, _)| title)
+        .collect::<Vec<_>>();
+    assert_eq!(titles, ["Warning (1):"]);
+    assert_eq!(
+        common::section_rows(&frame, "Warning"),
+        ["    retrying download         "]
+    );
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use cli_status_board::{Color, SBStateConfig, Status};
+
+#[test]
+fn lists_registered_statuses_in_their_own_section() {
+    let mut config = SBStateConfig::default();
+    // e.g. read from a config file, so not `'static`
+    let name = String::from("Uploading");
+    let uploading = config.register_status(&name, Some(Color::Blue), 2, false);
+    assert_eq!(uploading, Status::custom("Uploading"));
+
+    let frame = common::render(40, config, |state| {
+        (
+            state.add_task("upload", uploading),
+            state.add_task("build", Status::Started),
+        )
+    });
+    assert_eq!(frame.plain_lines()[0], "Finished tasks: 0 / 2");
+    assert_eq!(
+        common::section_rows(&frame, "Uploading"),
+        ["    upload                              "]
+    );
+}
+
+#[test]
+fn counts_and_lists_unregistered_statuses() {
+    let frame = common::render(40, SBStateConfig::default(), |state| {
+        (
+            state.add_task("x", Status::Custom("Unregistered")),
+            state.add_task("build", Status::Started),
+        )
+    });
+    assert_eq!(frame.plain_lines()[0], "Finished tasks: 0 / 2");
+
+    // After everything that was configured
+    let titles = common::sections(&frame)
+        .into_iter()
+        .map(|(title, _)| title)
+        .collect::<Vec<_>>();
+    assert_eq!(titles, ["Started (1):", "Unregistered (1):"]);
+    assert_eq!(
+        common::section_rows(&frame, "Unregistered"),
+        ["    x                                   "]
+    );
+}
+
+#[test]
+fn calls_out_abandoned_tasks_in_the_header() {
+    let frame = common::render(60, SBStateConfig::default(), |state| {
+        let cancelled = state.add_task("cancelled", Status::Started);
+        state.update_task(&cancelled, Status::Cancelled);
+        let skipped = state.add_task("skipped", Status::Skipped);
+        let finished = state.add_task("finished", Status::Started);
+        state.update_task(&finished, Status::Finished);
+        (cancelled, skipped, finished)
+    });
+    assert_eq!(
+        frame.plain_lines()[0],
+        "Finished tasks: 3 / 3 (1 cancelled, 1 skipped)"
+    );
+}
+
+#[test]
+fn keeps_statuses_set_before_the_handle_drops() {
+    let frame = common::render(60, SBStateConfig::default(), |state| {
+        let cancelled = state.add_task("cancelled", Status::Started);
+        state.update_task(&cancelled, Status::Cancelled);
+        let failed = state.add_task("failed", Status::Started);
+        state.update_task(&failed, Status::Error);
+        drop((cancelled, failed));
+    });
+    assert_eq!(
+        frame.plain_lines()[0],
+        "Finished tasks: 1 / 1 (1 cancelled)"
+    );
+    assert_eq!(
+        common::section_rows(&frame, "Error"),
+        ["    failed                                                  "]
+    );
+}
+
+#[test]
+fn times_tasks_with_unfinished_custom_statuses() {
+    let mut config = SBStateConfig {
+        show_elapsed: true,
+        ..Default::default()
+    };
+    let uploading = config.register_status("Uploading", None, 0, false);
+    let frame = common::render(40, config, |state| {
+        (
+            state.add_task("upload", uploading),
+            state.add_task("waiting", Status::Queued),
+        )
+    });
+
+    let upload = common::section_rows(&frame, "Uploading");
+    let cells = upload[0].split_whitespace().collect::<Vec<_>>();
+    assert_eq!(cells[0], "upload");
+    let seconds = cells[1].strip_suffix('s').unwrap().parse::<f64>();
+    assert!(seconds.is_ok(), "{:?}", upload);
+
+    // Nothing's running while it's still queued
+    assert_eq!(
+        common::section_rows(&frame, "Queued"),
+        ["    waiting                             "]
+    );
+}
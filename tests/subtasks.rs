@@ -0,0 +1,135 @@
+mod common;
+
+use cli_status_board::{SBStateConfig, Status};
+
+#[test]
+fn counts_subtasks_that_panicked_as_failed() {
+    let config = SBStateConfig {
+        error_on_panic: true,
+        ..Default::default()
+    };
+    let frame = common::render(60, config, |state| {
+        let task = state.add_task("parent", Status::Started);
+        drop(state.add_subtask(&task, Status::Started));
+
+        let subtask = state.add_subtask(&task, Status::Started);
+        let result = std::thread::spawn(move || {
+            let _subtask = subtask;
+            panic!("the subtask failed");
+        })
+        .join();
+        assert!(result.is_err());
+        task
+    });
+
+    assert_eq!(
+        common::section_rows(&frame, "Started"),
+        ["    parent    1 / 2, 1 failed [=============>.............]"]
+    );
+}
+
+#[test]
+fn lists_running_named_subtasks_by_default() {
+    let frame = common::render(40, SBStateConfig::default(), |state| {
+        let task = state.add_task("build", Status::Started);
+        let subtasks = vec![
+            state.add_named_subtask(&task, "compiling foo.rs", Status::Started),
+            state.add_named_subtask(&task, "compiling bar.rs", Status::Queued),
+            state.add_subtask(&task, Status::Started),
+        ];
+        (task, subtasks)
+    });
+
+    assert_eq!(
+        common::section_rows(&frame, "Started"),
+        [
+            "    build              0 / 3 [>.......]",
+            "      compiling foo.rs                  ",
+        ]
+    );
+}
+
+#[test]
+fn lists_queued_subtasks_when_asked_to() {
+    let config = SBStateConfig {
+        show_queued_subtasks: true,
+        max_subtasks_shown: 1,
+        ..Default::default()
+    };
+    let frame = common::render(40, config, |state| {
+        let task = state.add_task("build", Status::Started);
+        let subtasks = vec![
+            state.add_named_subtask(&task, "compiling bar.rs", Status::Queued),
+            state.add_named_subtask(&task, "compiling foo.rs", Status::Started),
+        ];
+        (task, subtasks)
+    });
+
+    // Started ones still come first, and the rest are cut
+    assert_eq!(
+        common::section_rows(&frame, "Started"),
+        [
+            "    build              0 / 2 [>.......]",
+            "      compiling foo.rs                  ",
+            "      ...                               ",
+        ]
+    );
+}
+
+#[test]
+fn keeps_subtasks_failed_after_their_handles_drop() {
+    let frame = common::render(60, SBStateConfig::default(), |state| {
+        let task = state.add_task("parent", Status::Started);
+        let failed = state.add_subtask(&task, Status::Started);
+        state.update_subtask(&task, &failed, Status::Error);
+        drop(failed);
+        let running = state.add_subtask(&task, Status::Started);
+        (task, running)
+    });
+
+    assert_eq!(
+        common::section_rows(&frame, "Started"),
+        ["    parent    0 / 2, 1 failed [>..........................]"]
+    );
+}
+
+#[test]
+fn rolls_progress_up_through_nested_subtasks() {
+    let config = SBStateConfig {
+        subtask_depth: 2,
+        ..Default::default()
+    };
+    let frame = common::render(60, config, |state| {
+        let stage = state.add_task("stage", Status::Started);
+        let compile = state.add_named_subtask(&stage, "compile", Status::Started);
+        let files = ["a.rs", "b.rs", "c.rs"]
+            .map(|file| state.add_named_subtask(&compile, file, Status::Started));
+        state.update_subtask(&compile, &files[0], Status::Finished);
+        state.update_subtask(&compile, &files[1], Status::Error);
+
+        // Finishing a whole job counts every one of its leaves as finished
+        let test = state.add_named_subtask(&stage, "test", Status::Started);
+        let cases = (0..2)
+            .map(|_| state.add_subtask(&test, Status::Started))
+            .collect::<Vec<_>>();
+        state.update_subtask(&stage, &test, Status::Finished);
+
+        // Deleting a job takes its leaves with it
+        let lint = state.add_named_subtask(&stage, "lint", Status::Started);
+        let checks = (0..4)
+            .map(|_| state.add_subtask(&lint, Status::Started))
+            .collect::<Vec<_>>();
+        state.delete_task(&lint);
+
+        (stage, compile, files, test, cases, lint, checks)
+    });
+
+    assert_eq!(
+        common::section_rows(&frame, "Started"),
+        [
+            "    stage        3 / 5, 1 failed [==============>.........]",
+            "      compile    1 / 3, 1 failed [=======>................]",
+            "        c.rs                                                ",
+        ]
+    );
+}
@@ -0,0 +1,111 @@
+use std::time::Duration;
+
+use cli_status_board::{MemoryRenderer, Retention, SBState, SBStateConfig, Status};
+
+#[test]
+fn summarizes_the_whole_run() {
+    let renderer = MemoryRenderer::new(40);
+    let config = SBStateConfig {
+        error_retention: Retention::keep_last(1),
+        summary_on_shutdown: true,
+        ..Default::default()
+    };
+    let state = SBState::with_renderer(config, renderer.clone());
+
+    let build = state.add_task("build", Status::Started);
+    let compile = state.add_named_subtask(&build, "compile", Status::Started);
+    state.update_subtask(&build, &compile, Status::Finished);
+    let link = state.add_named_subtask(&build, "link", Status::Started);
+    let deploy = state.add_task("deploy", Status::Queued);
+    let lint = state.add_task("lint", Status::Skipped);
+    state.error("flaky test");
+    state.error("timeout");
+    state.info("not a task");
+
+    let summary = state.summary();
+    assert_eq!(
+        summary.status_counts,
+        [
+            (Status::Started, 1),
+            (Status::Queued, 1),
+            (Status::Skipped, 1),
+            (Status::Error, 2),
+            (Status::Info, 1),
+        ]
+    );
+    assert_eq!(summary.subtask_completion, [("build".to_string(), 1, 2)]);
+    assert_eq!(summary.errors, ["flaky test", "timeout"]);
+
+    state.shutdown();
+    drop((build, compile, link, deploy, lint));
+
+    // Only the first error is gone by now, but it's still in the summary
+    let frame = renderer.last_frame().unwrap();
+    assert!(!frame.to_plain_string().contains("flaky test"));
+    let summary = state.summary();
+    assert_eq!(summary.errors, ["flaky test", "timeout"]);
+
+    let reports = renderer.reports();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].starts_with("Summary (took "), "{}", reports[0]);
+    assert!(
+        reports[0]
+            .ends_with("\nErrors (2):\n    flaky test\n    timeout\nSubtasks:\n    build: 1 / 2"),
+        "{}",
+        reports[0]
+    );
+}
+
+#[test]
+fn times_every_task() {
+    // Transitions are timed when the render thread gets to them, so keep it quick
+    let config = SBStateConfig {
+        refresh_rate: Duration::from_millis(1),
+        ..Default::default()
+    };
+    let state = SBState::with_renderer(config, MemoryRenderer::new(40));
+
+    let build = state.add_task("build", Status::Queued);
+    let deploy = state.add_task("deploy", Status::Queued);
+    std::thread::sleep(Duration::from_millis(100));
+    state.update_task(&build, Status::Started);
+    std::thread::sleep(Duration::from_millis(100));
+    state.update_task(&build, Status::Finished);
+
+    let summary = state.summary();
+    let [build_summary, deploy_summary] = &summary.tasks[..] else {
+        panic!("{:?}", summary.tasks);
+    };
+
+    assert_eq!(build_summary.name, "build");
+    assert_eq!(build_summary.status, Status::Finished);
+    assert!(build_summary.queue_wait >= Duration::from_millis(90));
+    assert!(build_summary.run_time >= Duration::from_millis(90));
+    assert!(build_summary.run_time < summary.wall_time - build_summary.queue_wait);
+    let statuses = build_summary
+        .history
+        .iter()
+        .map(|(_, status)| *status)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        statuses,
+        [Status::Queued, Status::Started, Status::Finished]
+    );
+    assert!(build_summary.history.is_sorted_by_key(|(time, _)| *time));
+    assert_eq!(
+        build_summary.history[2].0 - build_summary.history[1].0,
+        build_summary.run_time
+    );
+
+    // Still waiting, so it's been queued the whole time and hasn't run at all
+    assert_eq!(deploy_summary.name, "deploy");
+    assert!(deploy_summary.queue_wait >= Duration::from_millis(190));
+    assert_eq!(deploy_summary.run_time, Duration::ZERO);
+    assert_eq!(deploy_summary.history.len(), 1);
+
+    // Only the tasks that ran can be slow
+    assert_eq!(summary.slowest_tasks, std::slice::from_ref(build_summary));
+
+    drop((build, deploy));
+    state.shutdown();
+}
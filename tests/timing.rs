@@ -0,0 +1,51 @@
+mod common;
+
+use std::time::Duration;
+
+use cli_status_board::{SBStateConfig, Status};
+
+#[test]
+fn shows_elapsed_time_eta_and_rate() {
+    let config = SBStateConfig {
+        show_elapsed: true,
+        show_eta: true,
+        show_rate: true,
+        ..Default::default()
+    };
+    let frame = common::render(100, config, |state| {
+        let halfway = state.add_task("halfway", Status::Started);
+        let endless = state.add_task("endless", Status::Started);
+        // Long enough for the ETA of `endless` to overflow
+        std::thread::sleep(Duration::from_millis(1100));
+        state.set_progress(&halfway, 5, 10);
+        state.set_progress(&endless, 1, u64::MAX);
+        (halfway, endless)
+    });
+
+    let rows = common::section_rows(&frame, "Started");
+    let cells = |row: &str| {
+        row.split_whitespace()
+            .take_while(|cell| !cell.starts_with('['))
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+
+    // Halfway there, so it should take about as long again
+    let halfway = cells(&rows[0]);
+    assert_eq!(halfway[..4], ["halfway", "5", "/", "10"]);
+    let (elapsed, eta, rate) = (&halfway[4], &halfway[6], &halfway[7]);
+    let seconds = elapsed.strip_suffix('s').unwrap().parse::<f64>().unwrap();
+    assert!(seconds >= 1.1, "{:?}", rows);
+    assert_eq!(halfway[5], "ETA");
+    assert_eq!(eta, elapsed);
+    let rate = rate.strip_suffix("/s").unwrap().parse::<f64>().unwrap();
+    assert!(rate > 0.0 && rate < 5.0, "{:?}", rows);
+
+    // Far too long to estimate, so there's no ETA at all
+    let endless = cells(&rows[1]);
+    assert_eq!(
+        endless[..4],
+        ["endless", "1", "/", u64::MAX.to_string().as_str()]
+    );
+    assert!(!endless.contains(&"ETA".to_string()), "{:?}", rows);
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use cli_status_board::{SBStateConfig, Status, TaskNameTruncation, TaskNameWidth};
+
+const PATH: &str = "crates/foo/src/very/deep/module.rs";
+
+fn render_name(name: &str, truncation: TaskNameTruncation, ellipsis: &str) -> String {
+    let config = SBStateConfig {
+        // Leaves 20 characters for the name itself, after the padding
+        task_name_width: TaskNameWidth::ExactChars(25),
+        task_name_truncation: truncation,
+        ellipsis: ellipsis.to_string(),
+        grow_if_no_progress: false,
+        ..Default::default()
+    };
+    let frame = common::render(40, config, |state| state.add_task(name, Status::Started));
+    common::section_rows(&frame, "Started")[0]
+        .trim()
+        .to_string()
+}
+
+#[test]
+fn truncates_the_end() {
+    assert_eq!(
+        render_name(PATH, TaskNameTruncation::End, "..."),
+        "crates/foo/src/ve..."
+    );
+}
+
+#[test]
+fn truncates_the_start() {
+    assert_eq!(
+        render_name(PATH, TaskNameTruncation::Start, "..."),
+        "...ry/deep/module.rs"
+    );
+}
+
+#[test]
+fn truncates_the_middle() {
+    assert_eq!(
+        render_name(PATH, TaskNameTruncation::Middle, "..."),
+        "crates/fo...odule.rs"
+    );
+}
+
+#[test]
+fn collapses_leading_directories() {
+    assert_eq!(
+        render_name(PATH, TaskNameTruncation::Path, "..."),
+        ".../deep/module.rs"
+    );
+
+    // Too long a file name to keep whole, so keep as much of it as possible
+    assert_eq!(
+        render_name(
+            "src/a_really_long_file_name.rs",
+            TaskNameTruncation::Path,
+            "..."
+        ),
+        "...long_file_name.rs"
+    );
+
+    // Not a path at all
+    assert_eq!(
+        render_name(
+            "a task with a very long name",
+            TaskNameTruncation::Path,
+            "..."
+        ),
+        "a task with a ver..."
+    );
+}
+
+#[test]
+fn uses_a_custom_ellipsis() {
+    assert_eq!(
+        render_name(PATH, TaskNameTruncation::End, "…"),
+        "crates/foo/src/very…"
+    );
+    assert_eq!(
+        render_name(PATH, TaskNameTruncation::Path, "…"),
+        "…/deep/module.rs"
+    );
+}
+
+#[test]
+fn leaves_short_names_alone() {
+    for truncation in [
+        TaskNameTruncation::End,
+        TaskNameTruncation::Start,
+        TaskNameTruncation::Middle,
+        TaskNameTruncation::Path,
+    ] {
+        assert_eq!(render_name("src/lib.rs", truncation, "..."), "src/lib.rs");
+    }
+}
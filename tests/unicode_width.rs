@@ -0,0 +1,79 @@
+mod common;
+
+use cli_status_board::{SBStateConfig, Status};
+use unicode_width::UnicodeWidthStr;
+
+fn render_rows(width: usize, names: &[&str], with_progress: bool) -> Vec<String> {
+    let frame = common::render(width, SBStateConfig::default(), |state| {
+        names
+            .iter()
+            .map(|name| {
+                let task = state.add_task(name, Status::Started);
+                if with_progress {
+                    state.set_progress(&task, 1, 2);
+                }
+                task
+            })
+            .collect::<Vec<_>>()
+    });
+    common::section_rows(&frame, "Started")
+}
+
+// Which terminal column the progress counts end at.
+fn slash_column(row: &str) -> usize {
+    let (before, _) = row.split_once('/').unwrap();
+    before.width()
+}
+
+#[test]
+fn wide_names_fill_exactly_the_width() {
+    let rows = render_rows(
+        40,
+        &["日本語のタスク", "ascii task", "emoji 🚀 launch"],
+        false,
+    );
+    assert_eq!(rows.len(), 3);
+    for row in &rows {
+        assert_eq!(row.width(), 40, "{:?}", row);
+    }
+    assert!(rows[0].starts_with("    日本語のタスク "));
+}
+
+#[test]
+fn columns_line_up_after_wide_names() {
+    let rows = render_rows(50, &["日本語のタスク", "plain", "cafe\u{301} ☕"], true);
+    assert_eq!(rows.len(), 3);
+    let slash = slash_column(&rows[0]);
+    for row in &rows {
+        assert_eq!(slash_column(row), slash, "{:?}", rows);
+        assert_eq!(row.width(), rows[0].width(), "{:?}", rows);
+        assert!(row.width() <= 50, "{:?}", row);
+    }
+}
+
+#[test]
+fn truncates_on_grapheme_boundaries() {
+    let rows = render_rows(
+        20,
+        &["日本語のタスクはとても長いです", &"e\u{301}".repeat(30)],
+        false,
+    );
+    for row in &rows {
+        assert!(row.width() <= 20, "{:?}", row);
+        assert!(row.trim_end().ends_with("..."), "{:?}", row);
+    }
+    assert!(rows[0].starts_with("    日本語のタス..."), "{:?}", rows[0]);
+
+    // Accents stay attached to their letters
+    let kept = rows[1].trim().trim_end_matches("...");
+    assert_eq!(
+        kept.chars().filter(|c| *c == 'e').count() * 2,
+        kept.chars().count()
+    );
+}
+
+#[test]
+fn escape_codes_in_names_take_no_space() {
+    let rows = render_rows(30, &["\x1b[31mred\x1b[0m task", "red task"], false);
+    assert_eq!(rows[0], rows[1]);
+}
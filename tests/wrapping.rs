@@ -0,0 +1,96 @@
+mod common;
+
+use cli_status_board::{SBStateConfig, Status, TaskNameWidth};
+
+fn wrapping_config() -> SBStateConfig {
+    let mut config = SBStateConfig::default();
+    for section in config.sections.iter_mut() {
+        section.wrap = true;
+    }
+    config
+}
+
+#[test]
+fn keeps_the_progress_bar_on_the_first_line() {
+    let frame = common::render(40, wrapping_config(), |state| {
+        let task = state.add_task(
+            "a task with a very long name that needs wrapping",
+            Status::Started,
+        );
+        state.set_progress(&task, 1, 4);
+        task
+    });
+    assert_eq!(
+        common::section_rows(&frame, "Started"),
+        [
+            "    a task with a    1 / 4 [==>.......]",
+            "      very long",
+            "      name that",
+            "      needs",
+            "      wrapping",
+        ]
+    );
+}
+
+#[test]
+fn wraps_messages_across_the_whole_width() {
+    let frame = common::render(30, wrapping_config(), |state| {
+        state.error("failed to compile crates/foo/src/very/deep/module.rs");
+    });
+    assert_eq!(
+        common::section_rows(&frame, "Error"),
+        [
+            "    failed to compile         ",
+            "      crates/foo/src/very/dee",
+            "      p/module.rs",
+        ]
+    );
+}
+
+#[test]
+fn leaves_short_names_on_one_line() {
+    let frame = common::render(40, wrapping_config(), |state| {
+        state.add_task("short", Status::Started)
+    });
+    assert_eq!(
+        common::section_rows(&frame, "Started"),
+        ["    short                               "]
+    );
+}
+
+#[test]
+fn breaks_up_words_too_long_for_a_line() {
+    let config = SBStateConfig {
+        // Leaves 10 cells for the name itself, and 8 for each extra line
+        task_name_width: TaskNameWidth::ExactChars(15),
+        grow_if_no_progress: false,
+        ..wrapping_config()
+    };
+    let frame = common::render(40, config, |state| {
+        state.add_task("a bbbbbbbbbbbbbbbbbbbb", Status::Started)
+    });
+    assert_eq!(
+        common::section_rows(&frame, "Started"),
+        [
+            "    a          ",
+            "      bbbbbbbb",
+            "      bbbbbbbb",
+            "      bbbb",
+        ]
+    );
+}
+
+#[test]
+fn cuts_short_what_cant_be_wrapped() {
+    let config = SBStateConfig {
+        // Too narrow to fit any of these on a line of their own
+        task_name_width: TaskNameWidth::ExactChars(8),
+        ellipsis: "…".to_string(),
+        grow_if_no_progress: false,
+        ..wrapping_config()
+    };
+    let frame = common::render(40, config, |state| {
+        state.add_task("日本語", Status::Started)
+    });
+    assert_eq!(common::section_rows(&frame, "Started"), ["    日… "]);
+}